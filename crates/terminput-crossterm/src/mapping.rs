@@ -1,11 +1,13 @@
+use std::time::Duration;
+
 #[cfg(all(feature = "crossterm_0_28", not(feature = "crossterm_0_29")))]
 use crossterm_0_28 as crossterm;
 #[cfg(feature = "crossterm_0_29")]
 use crossterm_0_29 as crossterm;
 use terminput::{
-    Event, KeyCode, KeyEvent, KeyEventKind, KeyEventState, KeyModifiers, MediaKeyCode,
-    ModifierDirection, ModifierKeyCode, MouseButton, MouseEvent, MouseEventKind, ScrollDirection,
-    UnsupportedEvent,
+    Backend, Event, KeyCode, KeyEvent, KeyEventKind, KeyEventState, KeyModifiers, MediaKeyCode,
+    ModifierDirection, ModifierKeyCode, MouseButton, MouseEvent, MouseEventKind, PositionUnit,
+    ScrollDirection, TimedEvent, UnsupportedEvent,
 };
 
 /// Converts the crossterm [`Event`](crossterm::event::Event) to a terminput [`Event`].
@@ -25,6 +27,18 @@ pub fn to_terminput(value: crossterm::event::Event) -> Result<Event, Unsupported
     })
 }
 
+/// Converts the crossterm event to a terminput [`TimedEvent`] with a caller-supplied timestamp.
+pub fn to_terminput_timed(
+    value: crossterm::event::Event,
+    timestamp: Duration,
+) -> Result<TimedEvent, UnsupportedEvent> {
+    Ok(TimedEvent::new(
+        to_terminput(value)?,
+        timestamp,
+        Backend::Crossterm.into(),
+    ))
+}
+
 /// Converts the terminput [`Event`] to a crossterm [`Event`](crossterm::event::Event).
 pub fn to_crossterm(value: Event) -> Result<crossterm::event::Event, UnsupportedEvent> {
     Ok(match value {
@@ -41,18 +55,17 @@ pub fn to_crossterm(value: Event) -> Result<crossterm::event::Event, Unsupported
             rows.try_into()
                 .map_err(|e| UnsupportedEvent(format!("{e:?}")))?,
         ),
+        Event::Text(_) | Event::Reply(_) | Event::Ime(_) => {
+            Err(UnsupportedEvent(format!("{value:?}")))?
+        }
     })
 }
 
 /// Converts the crossterm [`MouseEvent`](crossterm::event::MouseEvent) to a terminput
 /// [`MouseEvent`].
 pub fn to_terminput_mouse(value: crossterm::event::MouseEvent) -> MouseEvent {
-    MouseEvent {
-        kind: to_terminput_mouse_kind(value.kind),
-        column: value.column,
-        row: value.row,
-        modifiers: to_terminput_key_modifiers(value.modifiers),
-    }
+    MouseEvent::new(to_terminput_mouse_kind(value.kind), value.column, value.row)
+        .modifiers(to_terminput_key_modifiers(value.modifiers))
 }
 
 /// Converts the terminput [`MouseEvent`] to a crossterm
@@ -60,6 +73,9 @@ pub fn to_terminput_mouse(value: crossterm::event::MouseEvent) -> MouseEvent {
 pub fn to_crossterm_mouse(
     value: MouseEvent,
 ) -> Result<crossterm::event::MouseEvent, UnsupportedEvent> {
+    if value.unit == PositionUnit::Pixels {
+        return Err(UnsupportedEvent(format!("{value:?}")));
+    }
     Ok(crossterm::event::MouseEvent {
         kind: to_crossterm_mouse_kind(value.kind)?,
         column: value.column,
@@ -135,18 +151,19 @@ fn to_crossterm_mouse_button(
         MouseButton::Left => crossterm::event::MouseButton::Left,
         MouseButton::Right => crossterm::event::MouseButton::Right,
         MouseButton::Middle => crossterm::event::MouseButton::Middle,
-        val @ MouseButton::Unknown => Err(UnsupportedEvent(format!("{val:?}")))?,
+        val @ (MouseButton::Back
+        | MouseButton::Forward
+        | MouseButton::Other(_)
+        | MouseButton::Unknown) => Err(UnsupportedEvent(format!("{val:?}")))?,
     })
 }
 
 /// Converts the crossterm [`KeyEvent`](crossterm::event::KeyEvent) to a terminput [`KeyEvent`].
 pub fn to_terminput_key(value: crossterm::event::KeyEvent) -> Result<KeyEvent, UnsupportedEvent> {
-    Ok(KeyEvent {
-        code: to_terminput_key_code(value.code)?,
-        modifiers: to_terminput_key_modifiers(value.modifiers),
-        kind: to_terminput_key_kind(value.kind),
-        state: to_terminput_key_state(value.state),
-    })
+    Ok(KeyEvent::new(to_terminput_key_code(value.code)?)
+        .modifiers(to_terminput_key_modifiers(value.modifiers))
+        .kind(to_terminput_key_kind(value.kind))
+        .state(to_terminput_key_state(value.state)))
 }
 
 /// Converts the terminput [`KeyEvent`] to a crossterm [`KeyEvent`](crossterm::event::KeyEvent).
@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 #[cfg(all(
     feature = "egui_0_32",
     not(feature = "egui_0_33"),
@@ -16,81 +18,115 @@ use egui_0_34 as egui;
 #[cfg(feature = "egui_0_35")]
 use egui_0_35 as egui;
 use terminput::{
-    Event, KeyCode, KeyEvent, KeyEventKind, KeyEventState, KeyModifiers, ModifierDirection,
-    ModifierKeyCode, MouseButton, MouseEvent, MouseEventKind, ScrollDirection, UnsupportedEvent,
+    Backend, CellGeometry, Event, ImeEvent, KeyCode, KeyEvent, KeyEventKind, KeyModifiers,
+    MouseButton, MouseEvent, MouseEventKind, PhysicalKey, ScrollAmount, ScrollDirection,
+    ScrollUnit, TimedEvent, UnsupportedEvent,
 };
+#[cfg(feature = "egui_0_35")]
+use terminput::{ModifierDirection, ModifierKeyCode};
 
 /// Converts the egui [`Event`](egui::Event) to a terminput [`Event`].
+///
+/// Pointer positions are used as cell positions as-is. Use [`to_terminput_with_geometry`] to
+/// convert them from pixels.
 pub fn to_terminput(value: egui::Event) -> Result<Event, UnsupportedEvent> {
+    to_terminput_with_geometry(value, &CellGeometry::default())
+}
+
+/// Converts the egui [`Event`](egui::Event) to a terminput [`Event`].
+///
+/// Pointer positions are converted to cell positions using the supplied [`CellGeometry`].
+pub fn to_terminput_with_geometry(
+    value: egui::Event,
+    geometry: &CellGeometry,
+) -> Result<Event, UnsupportedEvent> {
     match value {
         egui::Event::Paste(text) => Ok(Event::Paste(text)),
-        egui::Event::Text(_) => Err(UnsupportedEvent(format!("{value:?}"))),
+        egui::Event::Text(text) => Ok(Event::Text(text)),
         egui::Event::Key {
             key,
-            physical_key: _,
+            physical_key,
             pressed,
             repeat,
             modifiers,
-        } => Ok(Event::Key(KeyEvent {
-            code: key_code_to_terminput(key)?,
-            modifiers: key_modifiers_to_terminput(modifiers),
-            kind: if repeat {
-                KeyEventKind::Repeat
-            } else if pressed {
-                KeyEventKind::Press
-            } else {
-                KeyEventKind::Release
-            },
-            state: KeyEventState::empty(),
-        })),
-        egui::Event::PointerMoved(pos) => Ok(Event::Mouse(MouseEvent {
-            kind: MouseEventKind::Moved,
-            column: pos.x as u16,
-            row: pos.y as u16,
-            modifiers: KeyModifiers::empty(),
+        } => Ok(Event::Key({
+            let mut key_event = KeyEvent::new(key_code_to_terminput(key)?)
+                .modifiers(key_modifiers_to_terminput(modifiers))
+                .kind(if repeat {
+                    KeyEventKind::Repeat
+                } else if pressed {
+                    KeyEventKind::Press
+                } else {
+                    KeyEventKind::Release
+                });
+            key_event.physical_key = physical_key.and_then(physical_key_to_terminput);
+            key_event
         })),
+        egui::Event::PointerMoved(pos) => {
+            let (column, row) = geometry.to_cell(pos.x, pos.y);
+            Ok(Event::Mouse(MouseEvent::new(
+                MouseEventKind::Moved,
+                column,
+                row,
+            )))
+        }
 
         egui::Event::PointerButton {
             pos,
             button,
             pressed,
             modifiers,
-        } => Ok(Event::Mouse(MouseEvent {
-            kind: if pressed {
-                MouseEventKind::Down(mouse_button_to_terminput(button))
-            } else {
-                MouseEventKind::Up(mouse_button_to_terminput(button))
-            },
-            column: pos.x as u16,
-            row: pos.y as u16,
-            modifiers: key_modifiers_to_terminput(modifiers),
-        })),
+        } => {
+            let (column, row) = geometry.to_cell(pos.x, pos.y);
+            Ok(Event::Mouse(
+                MouseEvent::new(
+                    if pressed {
+                        MouseEventKind::Down(mouse_button_to_terminput(button))
+                    } else {
+                        MouseEventKind::Up(mouse_button_to_terminput(button))
+                    },
+                    column,
+                    row,
+                )
+                .modifiers(key_modifiers_to_terminput(modifiers)),
+            ))
+        }
         egui::Event::MouseWheel {
-            unit: _,
+            unit,
             delta,
             modifiers,
             #[cfg(any(feature = "egui_0_34", feature = "egui_0_35"))]
                 phase: _,
-        } => Ok(Event::Mouse(MouseEvent {
-            kind: MouseEventKind::Scroll(if delta.y < 0.0 {
+        } => {
+            let direction = if delta.y < 0.0 {
                 ScrollDirection::Down
             } else if delta.y > 0.0 {
                 ScrollDirection::Up
-            } else if delta.x < 0.0 {
+            } else if delta.x > 0.0 {
+                // Like the vertical delta, a positive value moves the content towards the
+                // positive axis, which matches `ScrollDirection::delta`
                 ScrollDirection::Left
-            } else {
+            } else if delta.x < 0.0 {
                 ScrollDirection::Right
-            }),
-            column: 0,
-            row: 0,
-            modifiers: key_modifiers_to_terminput(modifiers),
-        })),
+            } else {
+                return Err(UnsupportedEvent(format!("{value:?}")));
+            };
+            Ok(Event::Mouse(
+                MouseEvent::new(MouseEventKind::Scroll(direction), 0, 0)
+                    .modifiers(key_modifiers_to_terminput(modifiers))
+                    .scroll_amount(ScrollAmount::new(
+                        delta.x,
+                        delta.y,
+                        scroll_unit_to_terminput(unit),
+                    )),
+            ))
+        }
+        egui::Event::Ime(ime_event) => Ok(Event::Ime(ime_event_to_terminput(ime_event))),
         egui::Event::WindowFocused(true) => Ok(Event::FocusGained),
         egui::Event::WindowFocused(false) => Ok(Event::FocusLost),
         egui::Event::Copy
         | egui::Event::Cut
         | egui::Event::MouseMoved(_)
-        | egui::Event::Ime(_)
         | egui::Event::PointerGone
         | egui::Event::Zoom(_)
         | egui::Event::Touch { .. }
@@ -102,59 +138,233 @@ pub fn to_terminput(value: egui::Event) -> Result<Event, UnsupportedEvent> {
     }
 }
 
+/// Converts the egui [`Event`](egui::Event) to a terminput [`TimedEvent`].
+///
+/// egui events don't carry their own timestamps, so this should be given the time of the input
+/// frame, such as [`RawInput::time`](egui::RawInput::time).
+pub fn to_terminput_timed(
+    value: egui::Event,
+    timestamp: Duration,
+) -> Result<TimedEvent, UnsupportedEvent> {
+    to_terminput_timed_with_geometry(value, &CellGeometry::default(), timestamp)
+}
+
+/// Converts the egui [`Event`](egui::Event) to a terminput [`TimedEvent`], converting pointer
+/// positions using the supplied [`CellGeometry`].
+pub fn to_terminput_timed_with_geometry(
+    value: egui::Event,
+    geometry: &CellGeometry,
+    timestamp: Duration,
+) -> Result<TimedEvent, UnsupportedEvent> {
+    Ok(TimedEvent::new(
+        to_terminput_with_geometry(value, geometry)?,
+        timestamp,
+        Backend::Egui.into(),
+    ))
+}
+
 /// Converts the terminput [`Event`] to an egui [`Event`](egui::Event).
+///
+/// Mouse positions are used as pointer positions as-is. Use [`to_egui_with_geometry`] to convert
+/// them to pixels.
 pub fn to_egui(value: Event) -> Result<egui::Event, UnsupportedEvent> {
+    event_to_egui(value, None)
+}
+
+/// Converts the terminput [`Event`] to an egui [`Event`](egui::Event).
+///
+/// Mouse positions are converted to pixel positions using the supplied [`CellGeometry`]. Cell
+/// positions map to the center of the cell.
+pub fn to_egui_with_geometry(
+    value: Event,
+    geometry: &CellGeometry,
+) -> Result<egui::Event, UnsupportedEvent> {
+    event_to_egui(value, Some(geometry))
+}
+
+fn event_to_egui(
+    value: Event,
+    geometry: Option<&CellGeometry>,
+) -> Result<egui::Event, UnsupportedEvent> {
     Ok(match value {
-        Event::Key(key_event) => egui::Event::Key {
-            key: key_code_to_egui(key_event.code)?,
-            physical_key: None,
-            pressed: key_event.kind != KeyEventKind::Release,
-            repeat: key_event.kind == KeyEventKind::Repeat,
-            modifiers: key_modifiers_to_egui(key_event.modifiers),
+        Event::Key(key_event) => match key_code_to_egui(key_event.code) {
+            Ok(key) => egui::Event::Key {
+                key,
+                physical_key: key_event.physical_key.and_then(physical_key_to_egui),
+                pressed: key_event.kind != KeyEventKind::Release,
+                repeat: key_event.kind == KeyEventKind::Repeat,
+                modifiers: key_modifiers_to_egui(key_event.modifiers),
+            },
+            // egui has no key for most characters, but unmodified presses can be sent as text
+            Err(e) => match key_event.code {
+                KeyCode::Char(c)
+                    if key_event.kind != KeyEventKind::Release
+                        && !key_event
+                            .modifiers
+                            .intersects(KeyModifiers::CTRL | KeyModifiers::ALT) =>
+                {
+                    egui::Event::Text(c.to_string())
+                }
+                _ => Err(e)?,
+            },
         },
         Event::Mouse(mouse_event) => match mouse_event.kind {
             MouseEventKind::Down(mouse_button) | MouseEventKind::Drag(mouse_button) => {
                 egui::Event::PointerButton {
-                    pos: egui::Pos2 {
-                        x: mouse_event.column as f32,
-                        y: mouse_event.row as f32,
-                    },
-                    button: mouse_button_to_egui(mouse_button),
+                    pos: pos_to_egui(&mouse_event, geometry),
+                    button: mouse_button_to_egui(mouse_button)?,
                     pressed: true,
                     modifiers: key_modifiers_to_egui(mouse_event.modifiers),
                 }
             }
             MouseEventKind::Up(mouse_button) => egui::Event::PointerButton {
-                pos: egui::Pos2 {
-                    x: mouse_event.column as f32,
-                    y: mouse_event.row as f32,
-                },
-                button: mouse_button_to_egui(mouse_button),
+                pos: pos_to_egui(&mouse_event, geometry),
+                button: mouse_button_to_egui(mouse_button)?,
                 pressed: false,
                 modifiers: key_modifiers_to_egui(mouse_event.modifiers),
             },
-            MouseEventKind::Moved => egui::Event::PointerMoved(egui::Pos2 {
-                x: mouse_event.column as f32,
-                y: mouse_event.row as f32,
-            }),
-            MouseEventKind::Scroll(scroll_direction) => egui::Event::MouseWheel {
-                unit: egui::MouseWheelUnit::Line,
-                delta: egui::Vec2 {
-                    x: scroll_direction.delta().x as f32,
-                    y: scroll_direction.delta().y as f32,
-                },
-                #[cfg(any(feature = "egui_0_34", feature = "egui_0_35"))]
-                phase: egui::TouchPhase::Move,
-                modifiers: key_modifiers_to_egui(mouse_event.modifiers),
-            },
+            MouseEventKind::Moved => egui::Event::PointerMoved(pos_to_egui(&mouse_event, geometry)),
+            MouseEventKind::Scroll(scroll_direction) => {
+                let (unit, delta) = if let Some(amount) = mouse_event.scroll_amount {
+                    (
+                        scroll_unit_to_egui(amount.unit),
+                        egui::Vec2 {
+                            x: amount.x,
+                            y: amount.y,
+                        },
+                    )
+                } else {
+                    (
+                        egui::MouseWheelUnit::Line,
+                        egui::Vec2 {
+                            x: scroll_direction.delta().x as f32,
+                            y: scroll_direction.delta().y as f32,
+                        },
+                    )
+                };
+                egui::Event::MouseWheel {
+                    unit,
+                    delta,
+                    #[cfg(any(feature = "egui_0_34", feature = "egui_0_35"))]
+                    phase: egui::TouchPhase::Move,
+                    modifiers: key_modifiers_to_egui(mouse_event.modifiers),
+                }
+            }
         },
         Event::Paste(text) => egui::Event::Paste(text),
+        Event::Text(text) => egui::Event::Text(text),
         Event::FocusGained => egui::Event::WindowFocused(true),
         Event::FocusLost => egui::Event::WindowFocused(false),
-        Event::Resize { .. } => Err(UnsupportedEvent(format!("{value:?}")))?,
+        Event::Ime(ime_event) => egui::Event::Ime(ime_event_to_egui(ime_event)?),
+        Event::Resize { .. } | Event::Reply(_) => Err(UnsupportedEvent(format!("{value:?}")))?,
+    })
+}
+
+fn pos_to_egui(mouse_event: &MouseEvent, geometry: Option<&CellGeometry>) -> egui::Pos2 {
+    let (x, y) = match geometry {
+        Some(geometry) => geometry.from_position(mouse_event),
+        None => (f32::from(mouse_event.column), f32::from(mouse_event.row)),
+    };
+    egui::Pos2 { x, y }
+}
+
+#[cfg(not(feature = "egui_0_35"))]
+fn ime_event_to_terminput(value: egui::ImeEvent) -> ImeEvent {
+    match value {
+        egui::ImeEvent::Enabled => ImeEvent::Enabled,
+        egui::ImeEvent::Preedit(text) => ImeEvent::preedit(text),
+        egui::ImeEvent::Commit(text) => ImeEvent::Commit(text),
+        egui::ImeEvent::Disabled => ImeEvent::Disabled,
+    }
+}
+
+#[cfg(feature = "egui_0_35")]
+fn ime_event_to_terminput(value: egui::ImeEvent) -> ImeEvent {
+    match value {
+        #[allow(deprecated)]
+        egui::ImeEvent::Enabled => ImeEvent::Enabled,
+        egui::ImeEvent::Preedit {
+            text,
+            active_range_chars,
+        } => {
+            // egui reports the range in chars, but terminput uses byte offsets
+            let cursor = active_range_chars.map(|range| {
+                (
+                    char_to_byte_offset(&text, range.start),
+                    char_to_byte_offset(&text, range.end),
+                )
+            });
+            ImeEvent::Preedit { text, cursor }
+        }
+        egui::ImeEvent::Commit(text) => ImeEvent::Commit(text),
+        #[allow(deprecated)]
+        egui::ImeEvent::Disabled => ImeEvent::Disabled,
+    }
+}
+
+#[cfg(not(feature = "egui_0_35"))]
+fn ime_event_to_egui(value: ImeEvent) -> Result<egui::ImeEvent, UnsupportedEvent> {
+    Ok(match value {
+        ImeEvent::Enabled => egui::ImeEvent::Enabled,
+        ImeEvent::Preedit { text, cursor: _ } => egui::ImeEvent::Preedit(text),
+        ImeEvent::Commit(text) => egui::ImeEvent::Commit(text),
+        ImeEvent::Disabled => egui::ImeEvent::Disabled,
     })
 }
 
+#[cfg(feature = "egui_0_35")]
+fn ime_event_to_egui(value: ImeEvent) -> Result<egui::ImeEvent, UnsupportedEvent> {
+    Ok(match value {
+        // egui no longer has an equivalent for this
+        ImeEvent::Enabled => Err(UnsupportedEvent(format!("{value:?}")))?,
+        ImeEvent::Preedit { text, cursor } => {
+            let active_range_chars = cursor.map(|(start, end)| {
+                byte_to_char_offset(&text, start)..byte_to_char_offset(&text, end)
+            });
+            egui::ImeEvent::Preedit {
+                text,
+                active_range_chars,
+            }
+        }
+        ImeEvent::Commit(text) => egui::ImeEvent::Commit(text),
+        // An empty preedit dismisses the IME
+        ImeEvent::Disabled => egui::ImeEvent::Preedit {
+            text: String::new(),
+            active_range_chars: None,
+        },
+    })
+}
+
+#[cfg(feature = "egui_0_35")]
+fn char_to_byte_offset(text: &str, chars: usize) -> usize {
+    text.char_indices()
+        .nth(chars)
+        .map_or(text.len(), |(offset, _)| offset)
+}
+
+#[cfg(feature = "egui_0_35")]
+fn byte_to_char_offset(text: &str, bytes: usize) -> usize {
+    text.char_indices()
+        .take_while(|(offset, _)| *offset < bytes)
+        .count()
+}
+
+fn scroll_unit_to_terminput(value: egui::MouseWheelUnit) -> ScrollUnit {
+    match value {
+        egui::MouseWheelUnit::Point => ScrollUnit::Pixels,
+        egui::MouseWheelUnit::Line => ScrollUnit::Lines,
+        egui::MouseWheelUnit::Page => ScrollUnit::Pages,
+    }
+}
+
+fn scroll_unit_to_egui(value: ScrollUnit) -> egui::MouseWheelUnit {
+    match value {
+        ScrollUnit::Pixels => egui::MouseWheelUnit::Point,
+        ScrollUnit::Lines => egui::MouseWheelUnit::Line,
+        ScrollUnit::Pages => egui::MouseWheelUnit::Page,
+    }
+}
+
 fn key_code_to_terminput(value: egui::Key) -> Result<KeyCode, UnsupportedEvent> {
     match value {
         egui::Key::ArrowDown => Ok(KeyCode::Down),
@@ -426,6 +636,238 @@ fn key_code_to_egui(value: KeyCode) -> Result<egui::Key, UnsupportedEvent> {
     })
 }
 
+fn physical_key_to_terminput(value: egui::Key) -> Option<PhysicalKey> {
+    Some(match value {
+        egui::Key::ArrowDown => PhysicalKey::ArrowDown,
+        egui::Key::ArrowLeft => PhysicalKey::ArrowLeft,
+        egui::Key::ArrowRight => PhysicalKey::ArrowRight,
+        egui::Key::ArrowUp => PhysicalKey::ArrowUp,
+        egui::Key::Escape => PhysicalKey::Escape,
+        egui::Key::Tab => PhysicalKey::Tab,
+        egui::Key::Backspace => PhysicalKey::Backspace,
+        egui::Key::Enter => PhysicalKey::Enter,
+        egui::Key::Space => PhysicalKey::Space,
+        egui::Key::Insert => PhysicalKey::Insert,
+        egui::Key::Delete => PhysicalKey::Delete,
+        egui::Key::Home => PhysicalKey::Home,
+        egui::Key::End => PhysicalKey::End,
+        egui::Key::PageUp => PhysicalKey::PageUp,
+        egui::Key::PageDown => PhysicalKey::PageDown,
+        egui::Key::Copy => PhysicalKey::Copy,
+        egui::Key::Cut => PhysicalKey::Cut,
+        egui::Key::Paste => PhysicalKey::Paste,
+        egui::Key::Comma => PhysicalKey::Comma,
+        egui::Key::Backslash => PhysicalKey::Backslash,
+        egui::Key::Slash => PhysicalKey::Slash,
+        egui::Key::OpenBracket => PhysicalKey::BracketLeft,
+        egui::Key::CloseBracket => PhysicalKey::BracketRight,
+        egui::Key::Backtick => PhysicalKey::Backquote,
+        egui::Key::Minus => PhysicalKey::Minus,
+        egui::Key::Period => PhysicalKey::Period,
+        egui::Key::Equals => PhysicalKey::Equal,
+        egui::Key::Semicolon => PhysicalKey::Semicolon,
+        egui::Key::Quote => PhysicalKey::Quote,
+        egui::Key::Num0 => PhysicalKey::Digit0,
+        egui::Key::Num1 => PhysicalKey::Digit1,
+        egui::Key::Num2 => PhysicalKey::Digit2,
+        egui::Key::Num3 => PhysicalKey::Digit3,
+        egui::Key::Num4 => PhysicalKey::Digit4,
+        egui::Key::Num5 => PhysicalKey::Digit5,
+        egui::Key::Num6 => PhysicalKey::Digit6,
+        egui::Key::Num7 => PhysicalKey::Digit7,
+        egui::Key::Num8 => PhysicalKey::Digit8,
+        egui::Key::Num9 => PhysicalKey::Digit9,
+        egui::Key::A => PhysicalKey::KeyA,
+        egui::Key::B => PhysicalKey::KeyB,
+        egui::Key::C => PhysicalKey::KeyC,
+        egui::Key::D => PhysicalKey::KeyD,
+        egui::Key::E => PhysicalKey::KeyE,
+        egui::Key::F => PhysicalKey::KeyF,
+        egui::Key::G => PhysicalKey::KeyG,
+        egui::Key::H => PhysicalKey::KeyH,
+        egui::Key::I => PhysicalKey::KeyI,
+        egui::Key::J => PhysicalKey::KeyJ,
+        egui::Key::K => PhysicalKey::KeyK,
+        egui::Key::L => PhysicalKey::KeyL,
+        egui::Key::M => PhysicalKey::KeyM,
+        egui::Key::N => PhysicalKey::KeyN,
+        egui::Key::O => PhysicalKey::KeyO,
+        egui::Key::P => PhysicalKey::KeyP,
+        egui::Key::Q => PhysicalKey::KeyQ,
+        egui::Key::R => PhysicalKey::KeyR,
+        egui::Key::S => PhysicalKey::KeyS,
+        egui::Key::T => PhysicalKey::KeyT,
+        egui::Key::U => PhysicalKey::KeyU,
+        egui::Key::V => PhysicalKey::KeyV,
+        egui::Key::W => PhysicalKey::KeyW,
+        egui::Key::X => PhysicalKey::KeyX,
+        egui::Key::Y => PhysicalKey::KeyY,
+        egui::Key::Z => PhysicalKey::KeyZ,
+        egui::Key::F1 => PhysicalKey::F1,
+        egui::Key::F2 => PhysicalKey::F2,
+        egui::Key::F3 => PhysicalKey::F3,
+        egui::Key::F4 => PhysicalKey::F4,
+        egui::Key::F5 => PhysicalKey::F5,
+        egui::Key::F6 => PhysicalKey::F6,
+        egui::Key::F7 => PhysicalKey::F7,
+        egui::Key::F8 => PhysicalKey::F8,
+        egui::Key::F9 => PhysicalKey::F9,
+        egui::Key::F10 => PhysicalKey::F10,
+        egui::Key::F11 => PhysicalKey::F11,
+        egui::Key::F12 => PhysicalKey::F12,
+        egui::Key::F13 => PhysicalKey::F13,
+        egui::Key::F14 => PhysicalKey::F14,
+        egui::Key::F15 => PhysicalKey::F15,
+        egui::Key::F16 => PhysicalKey::F16,
+        egui::Key::F17 => PhysicalKey::F17,
+        egui::Key::F18 => PhysicalKey::F18,
+        egui::Key::F19 => PhysicalKey::F19,
+        egui::Key::F20 => PhysicalKey::F20,
+        egui::Key::F21 => PhysicalKey::F21,
+        egui::Key::F22 => PhysicalKey::F22,
+        egui::Key::F23 => PhysicalKey::F23,
+        egui::Key::F24 => PhysicalKey::F24,
+        egui::Key::BrowserBack => PhysicalKey::BrowserBack,
+        // egui reports the unshifted key, but these are handled in case a backend doesn't
+        egui::Key::Colon => PhysicalKey::Semicolon,
+        egui::Key::Pipe => PhysicalKey::Backslash,
+        egui::Key::Questionmark => PhysicalKey::Slash,
+        egui::Key::Exclamationmark => PhysicalKey::Digit1,
+        egui::Key::OpenCurlyBracket => PhysicalKey::BracketLeft,
+        egui::Key::CloseCurlyBracket => PhysicalKey::BracketRight,
+        egui::Key::Plus => PhysicalKey::Equal,
+        #[cfg(feature = "egui_0_35")]
+        egui::Key::ShiftLeft => PhysicalKey::ShiftLeft,
+        #[cfg(feature = "egui_0_35")]
+        egui::Key::ShiftRight => PhysicalKey::ShiftRight,
+        #[cfg(feature = "egui_0_35")]
+        egui::Key::ControlLeft => PhysicalKey::ControlLeft,
+        #[cfg(feature = "egui_0_35")]
+        egui::Key::ControlRight => PhysicalKey::ControlRight,
+        #[cfg(feature = "egui_0_35")]
+        egui::Key::AltLeft => PhysicalKey::AltLeft,
+        #[cfg(feature = "egui_0_35")]
+        egui::Key::AltRight => PhysicalKey::AltRight,
+        #[cfg(feature = "egui_0_35")]
+        egui::Key::SuperLeft => PhysicalKey::MetaLeft,
+        #[cfg(feature = "egui_0_35")]
+        egui::Key::SuperRight => PhysicalKey::MetaRight,
+        _ => return None,
+    })
+}
+
+fn physical_key_to_egui(value: PhysicalKey) -> Option<egui::Key> {
+    Some(match value {
+        PhysicalKey::ArrowDown => egui::Key::ArrowDown,
+        PhysicalKey::ArrowLeft => egui::Key::ArrowLeft,
+        PhysicalKey::ArrowRight => egui::Key::ArrowRight,
+        PhysicalKey::ArrowUp => egui::Key::ArrowUp,
+        PhysicalKey::Escape => egui::Key::Escape,
+        PhysicalKey::Tab => egui::Key::Tab,
+        PhysicalKey::Backspace => egui::Key::Backspace,
+        PhysicalKey::Enter => egui::Key::Enter,
+        PhysicalKey::Space => egui::Key::Space,
+        PhysicalKey::Insert => egui::Key::Insert,
+        PhysicalKey::Delete => egui::Key::Delete,
+        PhysicalKey::Home => egui::Key::Home,
+        PhysicalKey::End => egui::Key::End,
+        PhysicalKey::PageUp => egui::Key::PageUp,
+        PhysicalKey::PageDown => egui::Key::PageDown,
+        PhysicalKey::Copy => egui::Key::Copy,
+        PhysicalKey::Cut => egui::Key::Cut,
+        PhysicalKey::Paste => egui::Key::Paste,
+        PhysicalKey::Comma => egui::Key::Comma,
+        PhysicalKey::Backslash => egui::Key::Backslash,
+        PhysicalKey::Slash => egui::Key::Slash,
+        PhysicalKey::BracketLeft => egui::Key::OpenBracket,
+        PhysicalKey::BracketRight => egui::Key::CloseBracket,
+        PhysicalKey::Backquote => egui::Key::Backtick,
+        PhysicalKey::Minus => egui::Key::Minus,
+        PhysicalKey::Period => egui::Key::Period,
+        PhysicalKey::Equal => egui::Key::Equals,
+        PhysicalKey::Semicolon => egui::Key::Semicolon,
+        PhysicalKey::Quote => egui::Key::Quote,
+        PhysicalKey::Digit0 => egui::Key::Num0,
+        PhysicalKey::Digit1 => egui::Key::Num1,
+        PhysicalKey::Digit2 => egui::Key::Num2,
+        PhysicalKey::Digit3 => egui::Key::Num3,
+        PhysicalKey::Digit4 => egui::Key::Num4,
+        PhysicalKey::Digit5 => egui::Key::Num5,
+        PhysicalKey::Digit6 => egui::Key::Num6,
+        PhysicalKey::Digit7 => egui::Key::Num7,
+        PhysicalKey::Digit8 => egui::Key::Num8,
+        PhysicalKey::Digit9 => egui::Key::Num9,
+        PhysicalKey::KeyA => egui::Key::A,
+        PhysicalKey::KeyB => egui::Key::B,
+        PhysicalKey::KeyC => egui::Key::C,
+        PhysicalKey::KeyD => egui::Key::D,
+        PhysicalKey::KeyE => egui::Key::E,
+        PhysicalKey::KeyF => egui::Key::F,
+        PhysicalKey::KeyG => egui::Key::G,
+        PhysicalKey::KeyH => egui::Key::H,
+        PhysicalKey::KeyI => egui::Key::I,
+        PhysicalKey::KeyJ => egui::Key::J,
+        PhysicalKey::KeyK => egui::Key::K,
+        PhysicalKey::KeyL => egui::Key::L,
+        PhysicalKey::KeyM => egui::Key::M,
+        PhysicalKey::KeyN => egui::Key::N,
+        PhysicalKey::KeyO => egui::Key::O,
+        PhysicalKey::KeyP => egui::Key::P,
+        PhysicalKey::KeyQ => egui::Key::Q,
+        PhysicalKey::KeyR => egui::Key::R,
+        PhysicalKey::KeyS => egui::Key::S,
+        PhysicalKey::KeyT => egui::Key::T,
+        PhysicalKey::KeyU => egui::Key::U,
+        PhysicalKey::KeyV => egui::Key::V,
+        PhysicalKey::KeyW => egui::Key::W,
+        PhysicalKey::KeyX => egui::Key::X,
+        PhysicalKey::KeyY => egui::Key::Y,
+        PhysicalKey::KeyZ => egui::Key::Z,
+        PhysicalKey::F1 => egui::Key::F1,
+        PhysicalKey::F2 => egui::Key::F2,
+        PhysicalKey::F3 => egui::Key::F3,
+        PhysicalKey::F4 => egui::Key::F4,
+        PhysicalKey::F5 => egui::Key::F5,
+        PhysicalKey::F6 => egui::Key::F6,
+        PhysicalKey::F7 => egui::Key::F7,
+        PhysicalKey::F8 => egui::Key::F8,
+        PhysicalKey::F9 => egui::Key::F9,
+        PhysicalKey::F10 => egui::Key::F10,
+        PhysicalKey::F11 => egui::Key::F11,
+        PhysicalKey::F12 => egui::Key::F12,
+        PhysicalKey::F13 => egui::Key::F13,
+        PhysicalKey::F14 => egui::Key::F14,
+        PhysicalKey::F15 => egui::Key::F15,
+        PhysicalKey::F16 => egui::Key::F16,
+        PhysicalKey::F17 => egui::Key::F17,
+        PhysicalKey::F18 => egui::Key::F18,
+        PhysicalKey::F19 => egui::Key::F19,
+        PhysicalKey::F20 => egui::Key::F20,
+        PhysicalKey::F21 => egui::Key::F21,
+        PhysicalKey::F22 => egui::Key::F22,
+        PhysicalKey::F23 => egui::Key::F23,
+        PhysicalKey::F24 => egui::Key::F24,
+        PhysicalKey::BrowserBack => egui::Key::BrowserBack,
+        #[cfg(feature = "egui_0_35")]
+        PhysicalKey::ShiftLeft => egui::Key::ShiftLeft,
+        #[cfg(feature = "egui_0_35")]
+        PhysicalKey::ShiftRight => egui::Key::ShiftRight,
+        #[cfg(feature = "egui_0_35")]
+        PhysicalKey::ControlLeft => egui::Key::ControlLeft,
+        #[cfg(feature = "egui_0_35")]
+        PhysicalKey::ControlRight => egui::Key::ControlRight,
+        #[cfg(feature = "egui_0_35")]
+        PhysicalKey::AltLeft => egui::Key::AltLeft,
+        #[cfg(feature = "egui_0_35")]
+        PhysicalKey::AltRight => egui::Key::AltRight,
+        #[cfg(feature = "egui_0_35")]
+        PhysicalKey::MetaLeft => egui::Key::SuperLeft,
+        #[cfg(feature = "egui_0_35")]
+        PhysicalKey::MetaRight => egui::Key::SuperRight,
+        _ => return None,
+    })
+}
+
 fn key_modifiers_to_terminput(value: egui::Modifiers) -> KeyModifiers {
     let mut mapped = KeyModifiers::empty();
     if value.alt {
@@ -455,14 +897,236 @@ fn mouse_button_to_terminput(value: egui::PointerButton) -> MouseButton {
         egui::PointerButton::Primary => MouseButton::Left,
         egui::PointerButton::Secondary => MouseButton::Right,
         egui::PointerButton::Middle => MouseButton::Middle,
-        egui::PointerButton::Extra1 | egui::PointerButton::Extra2 => MouseButton::Unknown,
+        egui::PointerButton::Extra1 => MouseButton::Back,
+        egui::PointerButton::Extra2 => MouseButton::Forward,
     }
 }
 
-fn mouse_button_to_egui(value: MouseButton) -> egui::PointerButton {
-    match value {
+fn mouse_button_to_egui(value: MouseButton) -> Result<egui::PointerButton, UnsupportedEvent> {
+    Ok(match value {
         MouseButton::Left | MouseButton::Unknown => egui::PointerButton::Primary,
         MouseButton::Right => egui::PointerButton::Secondary,
         MouseButton::Middle => egui::PointerButton::Middle,
+        MouseButton::Back => egui::PointerButton::Extra1,
+        MouseButton::Forward => egui::PointerButton::Extra2,
+        val @ MouseButton::Other(_) => Err(UnsupportedEvent(format!("{val:?}")))?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mouse_wheel(x: f32, y: f32) -> egui::Event {
+        egui::Event::MouseWheel {
+            unit: egui::MouseWheelUnit::Line,
+            delta: egui::Vec2 { x, y },
+            modifiers: egui::Modifiers::NONE,
+            #[cfg(any(feature = "egui_0_34", feature = "egui_0_35"))]
+            phase: egui::TouchPhase::Move,
+        }
+    }
+
+    fn scroll_direction(event: egui::Event) -> Option<ScrollDirection> {
+        match to_terminput(event) {
+            Ok(Event::Mouse(MouseEvent {
+                kind: MouseEventKind::Scroll(direction),
+                ..
+            })) => Some(direction),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_scroll_direction() {
+        assert_eq!(
+            scroll_direction(mouse_wheel(0.0, 1.0)),
+            Some(ScrollDirection::Up)
+        );
+        assert_eq!(
+            scroll_direction(mouse_wheel(0.0, -1.0)),
+            Some(ScrollDirection::Down)
+        );
+        assert_eq!(
+            scroll_direction(mouse_wheel(1.0, 0.0)),
+            Some(ScrollDirection::Left)
+        );
+        assert_eq!(
+            scroll_direction(mouse_wheel(-1.0, 0.0)),
+            Some(ScrollDirection::Right)
+        );
+        assert!(to_terminput(mouse_wheel(0.0, 0.0)).is_err());
+    }
+
+    #[test]
+    fn test_scroll_direction_round_trip() {
+        for direction in [
+            ScrollDirection::Up,
+            ScrollDirection::Down,
+            ScrollDirection::Left,
+            ScrollDirection::Right,
+        ] {
+            let event = Event::Mouse(MouseEvent::new(MouseEventKind::Scroll(direction), 0, 0));
+            let egui_event = to_egui(event).ok();
+            assert_eq!(egui_event.and_then(scroll_direction), Some(direction));
+        }
+    }
+
+    fn mouse_position(event: egui::Event, geometry: &CellGeometry) -> Option<(u16, u16)> {
+        match to_terminput_with_geometry(event, geometry) {
+            Ok(Event::Mouse(mouse_event)) => Some((mouse_event.column, mouse_event.row)),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_fractional_scale_factor() {
+        let geometry = CellGeometry::new(9.0, 18.0)
+            .origin(10.0, 20.0)
+            .scale_factor(1.5);
+        // (30, 50) is (30, 45) physical pixels from the origin
+        let pos = egui::Pos2 { x: 30.0, y: 50.0 };
+        assert_eq!(
+            mouse_position(egui::Event::PointerMoved(pos), &geometry),
+            Some((3, 2))
+        );
+        // Positions above or to the left of the widget are clamped
+        let pos = egui::Pos2 { x: 0.0, y: 0.0 };
+        assert_eq!(
+            mouse_position(egui::Event::PointerMoved(pos), &geometry),
+            Some((0, 0))
+        );
+
+        let event = Event::Mouse(MouseEvent::new(MouseEventKind::Moved, 3, 2));
+        // Cells are converted to the position of their center
+        assert_eq!(
+            to_egui_with_geometry(event.clone(), &geometry).ok(),
+            Some(egui::Event::PointerMoved(egui::Pos2 { x: 31.0, y: 50.0 }))
+        );
+        // Without a geometry, positions are used as-is
+        assert_eq!(
+            to_egui(event).ok(),
+            Some(egui::Event::PointerMoved(egui::Pos2 { x: 3.0, y: 2.0 }))
+        );
+    }
+
+    #[test]
+    fn test_geometry_round_trip() {
+        let geometry = CellGeometry::new(7.5, 15.0)
+            .origin(4.0, 8.0)
+            .scale_factor(1.25);
+        for (column, row) in [(0, 0), (1, 1), (79, 23), (200, 60)] {
+            for kind in [
+                MouseEventKind::Moved,
+                MouseEventKind::Down(MouseButton::Left),
+                MouseEventKind::Up(MouseButton::Right),
+            ] {
+                let event = Event::Mouse(MouseEvent::new(kind, column, row));
+                let egui_event = to_egui_with_geometry(event.clone(), &geometry).ok();
+                assert_eq!(
+                    egui_event.and_then(|e| to_terminput_with_geometry(e, &geometry).ok()),
+                    Some(event)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_ime_commit() {
+        let egui_event = egui::Event::Ime(egui::ImeEvent::Commit("日本語".to_string()));
+        let event = Event::Ime(ImeEvent::Commit("日本語".to_string()));
+        assert_eq!(to_terminput(egui_event.clone()).ok(), Some(event.clone()));
+        assert_eq!(to_egui(event).ok(), Some(egui_event));
+    }
+
+    #[cfg(not(feature = "egui_0_35"))]
+    #[test]
+    fn test_ime_preedit() {
+        let egui_event = egui::Event::Ime(egui::ImeEvent::Preedit("にほんご".to_string()));
+        let event = Event::Ime(ImeEvent::preedit("にほんご"));
+        assert_eq!(to_terminput(egui_event.clone()).ok(), Some(event));
+        // egui doesn't support a cursor position before 0.35
+        let event = Event::Ime(ImeEvent::Preedit {
+            text: "にほんご".to_string(),
+            cursor: Some((3, 9)),
+        });
+        assert_eq!(to_egui(event).ok(), Some(egui_event));
+    }
+
+    #[cfg(feature = "egui_0_35")]
+    #[test]
+    fn test_ime_preedit() {
+        for (text, active_range_chars, cursor) in [
+            ("にほんご", Some(1..3), Some((3, 9))),
+            ("にほんご", Some(4..4), Some((12, 12))),
+            ("aé日本", Some(1..3), Some((1, 6))),
+            ("にほんご", None, None),
+        ] {
+            let egui_event = egui::Event::Ime(egui::ImeEvent::Preedit {
+                text: text.to_string(),
+                active_range_chars,
+            });
+            let event = Event::Ime(ImeEvent::Preedit {
+                text: text.to_string(),
+                cursor,
+            });
+            assert_eq!(to_terminput(egui_event.clone()).ok(), Some(event.clone()));
+            assert_eq!(to_egui(event).ok(), Some(egui_event));
+        }
+
+        // Offsets past the end of the text are clamped
+        assert_eq!(
+            to_terminput(egui::Event::Ime(egui::ImeEvent::Preedit {
+                text: "日本".to_string(),
+                active_range_chars: Some(1..5),
+            }))
+            .ok(),
+            Some(Event::Ime(ImeEvent::Preedit {
+                text: "日本".to_string(),
+                cursor: Some((3, 6)),
+            }))
+        );
+
+        assert!(to_egui(Event::Ime(ImeEvent::Enabled)).is_err());
+        assert_eq!(
+            to_egui(Event::Ime(ImeEvent::Disabled)).ok(),
+            Some(egui::Event::Ime(egui::ImeEvent::Preedit {
+                text: String::new(),
+                active_range_chars: None,
+            }))
+        );
+    }
+
+    #[test]
+    fn test_physical_key_round_trip() {
+        // Keys that are in different places on AZERTY and QWERTZ layouts
+        for (key, physical_key, code, terminput_physical_key) in [
+            (egui::Key::Q, egui::Key::A, 'q', PhysicalKey::KeyA),
+            (egui::Key::A, egui::Key::Q, 'a', PhysicalKey::KeyQ),
+            (egui::Key::Z, egui::Key::W, 'z', PhysicalKey::KeyW),
+            (
+                egui::Key::M,
+                egui::Key::Semicolon,
+                'm',
+                PhysicalKey::Semicolon,
+            ),
+            (egui::Key::Z, egui::Key::Y, 'z', PhysicalKey::KeyY),
+            (egui::Key::Num2, egui::Key::Num2, '2', PhysicalKey::Digit2),
+        ] {
+            let egui_event = egui::Event::Key {
+                key,
+                physical_key: Some(physical_key),
+                pressed: true,
+                repeat: false,
+                modifiers: egui::Modifiers::NONE,
+            };
+            let event = to_terminput(egui_event.clone()).ok();
+            let Some(Event::Key(key_event)) = &event else {
+                panic!("expected a key event for {key:?}");
+            };
+            assert_eq!(key_event.code, KeyCode::Char(code));
+            assert_eq!(key_event.physical_key, Some(terminput_physical_key));
+            assert_eq!(event.and_then(|e| to_egui(e).ok()), Some(egui_event));
+        }
     }
 }
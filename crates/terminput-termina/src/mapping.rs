@@ -1,9 +1,11 @@
+use std::time::Duration;
+
 #[cfg(feature = "termina_0_3")]
 use termina_0_3 as termina;
 use terminput::{
-    Event, KeyCode, KeyEvent, KeyEventKind, KeyEventState, KeyModifiers, MediaKeyCode,
-    ModifierDirection, ModifierKeyCode, MouseButton, MouseEvent, MouseEventKind, ScrollDirection,
-    UnsupportedEvent,
+    Backend, Event, KeyCode, KeyEvent, KeyEventKind, KeyEventState, KeyModifiers, MediaKeyCode,
+    ModifierDirection, ModifierKeyCode, MouseButton, MouseEvent, MouseEventKind, PositionUnit,
+    ScrollDirection, TimedEvent, UnsupportedEvent,
 };
 
 /// Converts the termina [`Event`](termina::Event) to a terminput [`Event`].
@@ -24,6 +26,18 @@ pub fn to_terminput(value: termina::Event) -> Result<Event, UnsupportedEvent> {
     })
 }
 
+/// Converts the termina event to a terminput [`TimedEvent`] with a caller-supplied timestamp.
+pub fn to_terminput_timed(
+    value: termina::Event,
+    timestamp: Duration,
+) -> Result<TimedEvent, UnsupportedEvent> {
+    Ok(TimedEvent::new(
+        to_terminput(value)?,
+        timestamp,
+        Backend::Termina.into(),
+    ))
+}
+
 /// Converts the terminput [`Event`] to a termina [`Event`](termina::Event).
 pub fn to_termina(value: Event) -> Result<termina::Event, UnsupportedEvent> {
     Ok(match value {
@@ -42,21 +56,23 @@ pub fn to_termina(value: Event) -> Result<termina::Event, UnsupportedEvent> {
             pixel_width: None,
             pixel_height: None,
         }),
+        Event::Text(_) | Event::Reply(_) | Event::Ime(_) => {
+            Err(UnsupportedEvent(format!("{value:?}")))?
+        }
     })
 }
 
 /// Converts the termina [`MouseEvent`](termina::event::MouseEvent) to a terminput [`MouseEvent`].
 pub fn to_terminput_mouse(value: termina::event::MouseEvent) -> MouseEvent {
-    MouseEvent {
-        kind: mouse_kind_to_terminput(value.kind),
-        column: value.column,
-        row: value.row,
-        modifiers: key_modifiers_to_terminput(value.modifiers),
-    }
+    MouseEvent::new(mouse_kind_to_terminput(value.kind), value.column, value.row)
+        .modifiers(key_modifiers_to_terminput(value.modifiers))
 }
 
 /// Converts the terminput [`MouseEvent`] to a termina [`MouseEvent`](termina::event::MouseEvent).
 pub fn to_termina_mouse(value: MouseEvent) -> Result<termina::event::MouseEvent, UnsupportedEvent> {
+    if value.unit == PositionUnit::Pixels {
+        return Err(UnsupportedEvent(format!("{value:?}")));
+    }
     Ok(termina::event::MouseEvent {
         kind: mouse_kind_to_termina(value.kind)?,
         column: value.column,
@@ -124,18 +140,19 @@ fn mouse_button_to_termina(
         MouseButton::Left => termina::event::MouseButton::Left,
         MouseButton::Right => termina::event::MouseButton::Right,
         MouseButton::Middle => termina::event::MouseButton::Middle,
-        val @ MouseButton::Unknown => Err(UnsupportedEvent(format!("{val:?}")))?,
+        val @ (MouseButton::Back
+        | MouseButton::Forward
+        | MouseButton::Other(_)
+        | MouseButton::Unknown) => Err(UnsupportedEvent(format!("{val:?}")))?,
     })
 }
 
 /// Converts the termina [`KeyEvent`](termina::event::KeyEvent) to a terminput [`KeyEvent`].
 pub fn to_terminput_key(value: termina::event::KeyEvent) -> Result<KeyEvent, UnsupportedEvent> {
-    Ok(KeyEvent {
-        code: key_code_to_terminput(value.code)?,
-        modifiers: key_modifiers_to_terminput(value.modifiers),
-        kind: key_kind_to_terminput(value.kind),
-        state: key_state_to_terminput(value.state),
-    })
+    Ok(KeyEvent::new(key_code_to_terminput(value.code)?)
+        .modifiers(key_modifiers_to_terminput(value.modifiers))
+        .kind(key_kind_to_terminput(value.kind))
+        .state(key_state_to_terminput(value.state)))
 }
 
 /// Converts the terminput [`KeyEvent`] to a termina [`KeyEvent`](termina::event::KeyEvent).
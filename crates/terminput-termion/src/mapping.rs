@@ -1,9 +1,11 @@
 #![deny(missing_docs)]
 #![doc = include_str!("../README.md")]
 
+use std::time::Duration;
+
 use terminput::{
-    Event, KeyCode, KeyEvent, KeyEventKind, KeyEventState, KeyModifiers, MouseButton, MouseEvent,
-    MouseEventKind, ScrollDirection, UnsupportedEvent,
+    Backend, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseButton, MouseEvent,
+    MouseEventKind, PositionUnit, ScrollDirection, TimedEvent, UnsupportedEvent,
 };
 #[cfg(feature = "termion_4")]
 use termion_4 as termion;
@@ -17,242 +19,89 @@ pub fn to_terminput(value: termion::event::Event) -> Result<Event, UnsupportedEv
     })
 }
 
+/// Converts the termion event to a terminput [`TimedEvent`] with a caller-supplied timestamp.
+pub fn to_terminput_timed(
+    value: termion::event::Event,
+    timestamp: Duration,
+) -> Result<TimedEvent, UnsupportedEvent> {
+    Ok(TimedEvent::new(
+        to_terminput(value)?,
+        timestamp,
+        Backend::Termion.into(),
+    ))
+}
+
 /// Converts the terminput [`Event`] to a termion [`Event`](termion::event::Event).
 pub fn to_termion(value: Event) -> Result<termion::event::Event, UnsupportedEvent> {
     Ok(match value {
         Event::Key(key_event) => termion::event::Event::Key(key_to_termion(key_event)?),
         Event::Mouse(mouse_event) => termion::event::Event::Mouse(to_termion_mouse(mouse_event)?),
-        Event::FocusGained | Event::FocusLost | Event::Paste(_) | Event::Resize { .. } => {
-            Err(UnsupportedEvent(format!("{value:?}")))?
-        }
+        Event::FocusGained
+        | Event::FocusLost
+        | Event::Paste(_)
+        | Event::Text(_)
+        | Event::Resize { .. }
+        | Event::Reply(_)
+        | Event::Ime(_) => Err(UnsupportedEvent(format!("{value:?}")))?,
     })
 }
 
 /// Converts the termion [`Key`](termion::event::Key) to a terminput [`KeyEvent`].
 pub fn to_terminput_key(value: termion::event::Key) -> Result<KeyEvent, UnsupportedEvent> {
     Ok(match value {
-        termion::event::Key::Backspace => KeyEvent {
-            code: KeyCode::Backspace,
-            modifiers: KeyModifiers::NONE,
-            kind: KeyEventKind::Press,
-            state: KeyEventState::empty(),
-        },
-        termion::event::Key::Left => KeyEvent {
-            code: KeyCode::Left,
-            modifiers: KeyModifiers::NONE,
-            kind: KeyEventKind::Press,
-            state: KeyEventState::empty(),
-        },
-        termion::event::Key::ShiftLeft => KeyEvent {
-            code: KeyCode::Left,
-            modifiers: KeyModifiers::SHIFT,
-            kind: KeyEventKind::Press,
-            state: KeyEventState::empty(),
-        },
-        termion::event::Key::AltLeft => KeyEvent {
-            code: KeyCode::Left,
-            modifiers: KeyModifiers::ALT,
-            kind: KeyEventKind::Press,
-            state: KeyEventState::empty(),
-        },
-        termion::event::Key::CtrlLeft => KeyEvent {
-            code: KeyCode::Left,
-            modifiers: KeyModifiers::CTRL,
-            kind: KeyEventKind::Press,
-            state: KeyEventState::empty(),
-        },
-        termion::event::Key::Right => KeyEvent {
-            code: KeyCode::Right,
-            modifiers: KeyModifiers::NONE,
-            kind: KeyEventKind::Press,
-            state: KeyEventState::empty(),
-        },
-        termion::event::Key::ShiftRight => KeyEvent {
-            code: KeyCode::Right,
-            modifiers: KeyModifiers::SHIFT,
-            kind: KeyEventKind::Press,
-            state: KeyEventState::empty(),
-        },
-        termion::event::Key::AltRight => KeyEvent {
-            code: KeyCode::Right,
-            modifiers: KeyModifiers::ALT,
-            kind: KeyEventKind::Press,
-            state: KeyEventState::empty(),
-        },
-        termion::event::Key::CtrlRight => KeyEvent {
-            code: KeyCode::Right,
-            modifiers: KeyModifiers::CTRL,
-            kind: KeyEventKind::Press,
-            state: KeyEventState::empty(),
-        },
-        termion::event::Key::Up => KeyEvent {
-            code: KeyCode::Up,
-            modifiers: KeyModifiers::NONE,
-            kind: KeyEventKind::Press,
-            state: KeyEventState::empty(),
-        },
-        termion::event::Key::ShiftUp => KeyEvent {
-            code: KeyCode::Up,
-            modifiers: KeyModifiers::SHIFT,
-            kind: KeyEventKind::Press,
-            state: KeyEventState::empty(),
-        },
-        termion::event::Key::AltUp => KeyEvent {
-            code: KeyCode::Up,
-            modifiers: KeyModifiers::ALT,
-            kind: KeyEventKind::Press,
-            state: KeyEventState::empty(),
-        },
-        termion::event::Key::CtrlUp => KeyEvent {
-            code: KeyCode::Up,
-            modifiers: KeyModifiers::CTRL,
-            kind: KeyEventKind::Press,
-            state: KeyEventState::empty(),
-        },
-        termion::event::Key::Down => KeyEvent {
-            code: KeyCode::Down,
-            modifiers: KeyModifiers::NONE,
-            kind: KeyEventKind::Press,
-            state: KeyEventState::empty(),
-        },
-        termion::event::Key::ShiftDown => KeyEvent {
-            code: KeyCode::Down,
-            modifiers: KeyModifiers::SHIFT,
-            kind: KeyEventKind::Press,
-            state: KeyEventState::empty(),
-        },
-        termion::event::Key::AltDown => KeyEvent {
-            code: KeyCode::Down,
-            modifiers: KeyModifiers::ALT,
-            kind: KeyEventKind::Press,
-            state: KeyEventState::empty(),
-        },
-        termion::event::Key::CtrlDown => KeyEvent {
-            code: KeyCode::Down,
-            modifiers: KeyModifiers::CTRL,
-            kind: KeyEventKind::Press,
-            state: KeyEventState::empty(),
-        },
-        termion::event::Key::Home => KeyEvent {
-            code: KeyCode::Home,
-            modifiers: KeyModifiers::NONE,
-            kind: KeyEventKind::Press,
-            state: KeyEventState::empty(),
-        },
-        termion::event::Key::CtrlHome => KeyEvent {
-            code: KeyCode::Home,
-            modifiers: KeyModifiers::CTRL,
-            kind: KeyEventKind::Press,
-            state: KeyEventState::empty(),
-        },
-        termion::event::Key::End => KeyEvent {
-            code: KeyCode::End,
-            modifiers: KeyModifiers::NONE,
-            kind: KeyEventKind::Press,
-            state: KeyEventState::empty(),
-        },
-        termion::event::Key::CtrlEnd => KeyEvent {
-            code: KeyCode::End,
-            modifiers: KeyModifiers::CTRL,
-            kind: KeyEventKind::Press,
-            state: KeyEventState::empty(),
-        },
-        termion::event::Key::PageUp => KeyEvent {
-            code: KeyCode::PageUp,
-            modifiers: KeyModifiers::NONE,
-            kind: KeyEventKind::Press,
-            state: KeyEventState::empty(),
-        },
-        termion::event::Key::PageDown => KeyEvent {
-            code: KeyCode::PageDown,
-            modifiers: KeyModifiers::NONE,
-            kind: KeyEventKind::Press,
-            state: KeyEventState::empty(),
-        },
-        termion::event::Key::BackTab => KeyEvent {
-            code: KeyCode::Tab,
-            modifiers: KeyModifiers::SHIFT,
-            kind: KeyEventKind::Press,
-            state: KeyEventState::empty(),
-        },
-        termion::event::Key::Delete => KeyEvent {
-            code: KeyCode::Delete,
-            modifiers: KeyModifiers::NONE,
-            kind: KeyEventKind::Press,
-            state: KeyEventState::empty(),
-        },
-        termion::event::Key::Insert => KeyEvent {
-            code: KeyCode::Insert,
-            modifiers: KeyModifiers::NONE,
-            kind: KeyEventKind::Press,
-            state: KeyEventState::empty(),
-        },
-        termion::event::Key::F(f) => KeyEvent {
-            code: KeyCode::F(f),
-            modifiers: KeyModifiers::NONE,
-            kind: KeyEventKind::Press,
-            state: KeyEventState::empty(),
-        },
-        termion::event::Key::Char('\n') => KeyEvent {
-            code: KeyCode::Enter,
-            modifiers: KeyModifiers::NONE,
-            kind: KeyEventKind::Press,
-            state: KeyEventState::empty(),
-        },
-        termion::event::Key::Char('\t') => KeyEvent {
-            code: KeyCode::Tab,
-            modifiers: KeyModifiers::NONE,
-            kind: KeyEventKind::Press,
-            state: KeyEventState::empty(),
-        },
-        termion::event::Key::Char(c) => KeyEvent {
-            code: KeyCode::Char(c),
-            modifiers: KeyModifiers::NONE,
-            kind: KeyEventKind::Press,
-            state: KeyEventState::empty(),
-        },
-        termion::event::Key::Alt('\n') => KeyEvent {
-            code: KeyCode::Enter,
-            modifiers: KeyModifiers::ALT,
-            kind: KeyEventKind::Press,
-            state: KeyEventState::empty(),
-        },
-        termion::event::Key::Alt('\t') => KeyEvent {
-            code: KeyCode::Tab,
-            modifiers: KeyModifiers::ALT,
-            kind: KeyEventKind::Press,
-            state: KeyEventState::empty(),
-        },
-        termion::event::Key::Alt(c) => KeyEvent {
-            code: KeyCode::Char(c),
-            modifiers: KeyModifiers::ALT,
-            kind: KeyEventKind::Press,
-            state: KeyEventState::empty(),
-        },
-        termion::event::Key::Ctrl('\n') => KeyEvent {
-            code: KeyCode::Enter,
-            modifiers: KeyModifiers::CTRL,
-            kind: KeyEventKind::Press,
-            state: KeyEventState::empty(),
-        },
-        termion::event::Key::Ctrl('\t') => KeyEvent {
-            code: KeyCode::Tab,
-            modifiers: KeyModifiers::CTRL,
-            kind: KeyEventKind::Press,
-            state: KeyEventState::empty(),
-        },
-        termion::event::Key::Ctrl(c) => KeyEvent {
-            code: KeyCode::Char(c),
-            modifiers: KeyModifiers::CTRL,
-            kind: KeyEventKind::Press,
-            state: KeyEventState::empty(),
-        },
-        termion::event::Key::Esc => KeyEvent {
-            code: KeyCode::Esc,
-            modifiers: KeyModifiers::NONE,
-            kind: KeyEventKind::Press,
-            state: KeyEventState::empty(),
-        },
+        termion::event::Key::Backspace => KeyEvent::new(KeyCode::Backspace),
+        termion::event::Key::Left => KeyEvent::new(KeyCode::Left),
+        termion::event::Key::ShiftLeft => {
+            KeyEvent::new(KeyCode::Left).modifiers(KeyModifiers::SHIFT)
+        }
+        termion::event::Key::AltLeft => KeyEvent::new(KeyCode::Left).modifiers(KeyModifiers::ALT),
+        termion::event::Key::CtrlLeft => KeyEvent::new(KeyCode::Left).modifiers(KeyModifiers::CTRL),
+        termion::event::Key::Right => KeyEvent::new(KeyCode::Right),
+        termion::event::Key::ShiftRight => {
+            KeyEvent::new(KeyCode::Right).modifiers(KeyModifiers::SHIFT)
+        }
+        termion::event::Key::AltRight => KeyEvent::new(KeyCode::Right).modifiers(KeyModifiers::ALT),
+        termion::event::Key::CtrlRight => {
+            KeyEvent::new(KeyCode::Right).modifiers(KeyModifiers::CTRL)
+        }
+        termion::event::Key::Up => KeyEvent::new(KeyCode::Up),
+        termion::event::Key::ShiftUp => KeyEvent::new(KeyCode::Up).modifiers(KeyModifiers::SHIFT),
+        termion::event::Key::AltUp => KeyEvent::new(KeyCode::Up).modifiers(KeyModifiers::ALT),
+        termion::event::Key::CtrlUp => KeyEvent::new(KeyCode::Up).modifiers(KeyModifiers::CTRL),
+        termion::event::Key::Down => KeyEvent::new(KeyCode::Down),
+        termion::event::Key::ShiftDown => {
+            KeyEvent::new(KeyCode::Down).modifiers(KeyModifiers::SHIFT)
+        }
+        termion::event::Key::AltDown => KeyEvent::new(KeyCode::Down).modifiers(KeyModifiers::ALT),
+        termion::event::Key::CtrlDown => KeyEvent::new(KeyCode::Down).modifiers(KeyModifiers::CTRL),
+        termion::event::Key::Home => KeyEvent::new(KeyCode::Home),
+        termion::event::Key::CtrlHome => KeyEvent::new(KeyCode::Home).modifiers(KeyModifiers::CTRL),
+        termion::event::Key::End => KeyEvent::new(KeyCode::End),
+        termion::event::Key::CtrlEnd => KeyEvent::new(KeyCode::End).modifiers(KeyModifiers::CTRL),
+        termion::event::Key::PageUp => KeyEvent::new(KeyCode::PageUp),
+        termion::event::Key::PageDown => KeyEvent::new(KeyCode::PageDown),
+        termion::event::Key::BackTab => KeyEvent::new(KeyCode::Tab).modifiers(KeyModifiers::SHIFT),
+        termion::event::Key::Delete => KeyEvent::new(KeyCode::Delete),
+        termion::event::Key::Insert => KeyEvent::new(KeyCode::Insert),
+        termion::event::Key::F(f) => KeyEvent::new(KeyCode::F(f)),
+        termion::event::Key::Char('\n') => KeyEvent::new(KeyCode::Enter),
+        termion::event::Key::Char('\t') => KeyEvent::new(KeyCode::Tab),
+        termion::event::Key::Char(c) => KeyEvent::new(KeyCode::Char(c)),
+        termion::event::Key::Alt('\n') => {
+            KeyEvent::new(KeyCode::Enter).modifiers(KeyModifiers::ALT)
+        }
+        termion::event::Key::Alt('\t') => KeyEvent::new(KeyCode::Tab).modifiers(KeyModifiers::ALT),
+        termion::event::Key::Alt(c) => KeyEvent::new(KeyCode::Char(c)).modifiers(KeyModifiers::ALT),
+        termion::event::Key::Ctrl('\n') => {
+            KeyEvent::new(KeyCode::Enter).modifiers(KeyModifiers::CTRL)
+        }
+        termion::event::Key::Ctrl('\t') => {
+            KeyEvent::new(KeyCode::Tab).modifiers(KeyModifiers::CTRL)
+        }
+        termion::event::Key::Ctrl(c) => {
+            KeyEvent::new(KeyCode::Char(c)).modifiers(KeyModifiers::CTRL)
+        }
+        termion::event::Key::Esc => KeyEvent::new(KeyCode::Esc),
         _ => Err(UnsupportedEvent(format!("{value:?}")))?,
     })
 }
@@ -328,78 +177,68 @@ pub fn key_to_termion(value: KeyEvent) -> Result<termion::event::Key, Unsupporte
 pub fn to_terminput_mouse(value: termion::event::MouseEvent) -> MouseEvent {
     match value {
         termion::event::MouseEvent::Press(termion::event::MouseButton::Left, column, row) => {
-            MouseEvent {
-                kind: MouseEventKind::Down(MouseButton::Left),
-                row: row - 1,
-                column: column - 1,
-                modifiers: KeyModifiers::NONE,
-            }
+            MouseEvent::new(MouseEventKind::Down(MouseButton::Left), column - 1, row - 1)
         }
         termion::event::MouseEvent::Press(termion::event::MouseButton::Right, column, row) => {
-            MouseEvent {
-                kind: MouseEventKind::Down(MouseButton::Right),
-                row: row - 1,
-                column: column - 1,
-                modifiers: KeyModifiers::NONE,
-            }
+            MouseEvent::new(
+                MouseEventKind::Down(MouseButton::Right),
+                column - 1,
+                row - 1,
+            )
         }
         termion::event::MouseEvent::Press(termion::event::MouseButton::Middle, column, row) => {
-            MouseEvent {
-                kind: MouseEventKind::Down(MouseButton::Right),
-                row: row - 1,
-                column: column - 1,
-                modifiers: KeyModifiers::NONE,
-            }
+            MouseEvent::new(
+                MouseEventKind::Down(MouseButton::Right),
+                column - 1,
+                row - 1,
+            )
         }
         termion::event::MouseEvent::Press(termion::event::MouseButton::WheelDown, row, column) => {
-            MouseEvent {
-                kind: MouseEventKind::Scroll(ScrollDirection::Down),
-                row: row - 1,
-                column: column - 1,
-                modifiers: KeyModifiers::NONE,
-            }
+            MouseEvent::new(
+                MouseEventKind::Scroll(ScrollDirection::Down),
+                column - 1,
+                row - 1,
+            )
         }
         termion::event::MouseEvent::Press(termion::event::MouseButton::WheelUp, row, column) => {
-            MouseEvent {
-                kind: MouseEventKind::Scroll(ScrollDirection::Up),
-                row: row - 1,
-                column: column - 1,
-                modifiers: KeyModifiers::NONE,
-            }
+            MouseEvent::new(
+                MouseEventKind::Scroll(ScrollDirection::Up),
+                column - 1,
+                row - 1,
+            )
         }
         termion::event::MouseEvent::Press(termion::event::MouseButton::WheelLeft, row, column) => {
-            MouseEvent {
-                kind: MouseEventKind::Scroll(ScrollDirection::Left),
-                row: row - 1,
-                column: column - 1,
-                modifiers: KeyModifiers::NONE,
-            }
+            MouseEvent::new(
+                MouseEventKind::Scroll(ScrollDirection::Left),
+                column - 1,
+                row - 1,
+            )
         }
         termion::event::MouseEvent::Press(termion::event::MouseButton::WheelRight, row, column) => {
-            MouseEvent {
-                kind: MouseEventKind::Scroll(ScrollDirection::Right),
-                row: row - 1,
-                column: column - 1,
-                modifiers: KeyModifiers::NONE,
-            }
+            MouseEvent::new(
+                MouseEventKind::Scroll(ScrollDirection::Right),
+                column - 1,
+                row - 1,
+            )
         }
-        termion::event::MouseEvent::Release(column, row) => MouseEvent {
-            kind: MouseEventKind::Up(MouseButton::Unknown),
-            row: row - 1,
-            column: column - 1,
-            modifiers: KeyModifiers::NONE,
-        },
-        termion::event::MouseEvent::Hold(column, row) => MouseEvent {
-            kind: MouseEventKind::Drag(MouseButton::Unknown),
-            row: row - 1,
-            column: column - 1,
-            modifiers: KeyModifiers::NONE,
-        },
+        termion::event::MouseEvent::Release(column, row) => MouseEvent::new(
+            MouseEventKind::Up(MouseButton::Unknown),
+            column - 1,
+            row - 1,
+        ),
+        termion::event::MouseEvent::Hold(column, row) => MouseEvent::new(
+            MouseEventKind::Drag(MouseButton::Unknown),
+            column - 1,
+            row - 1,
+        ),
     }
 }
 
 /// Converts the terminput [`MouseEvent`] to a termion [`MouseEvent`](termion::event::MouseEvent).
 pub fn to_termion_mouse(value: MouseEvent) -> Result<termion::event::MouseEvent, UnsupportedEvent> {
+    if value.unit == PositionUnit::Pixels {
+        return Err(UnsupportedEvent(format!("{value:?}")));
+    }
     let column = value.column + 1;
     let row = value.row + 1;
     Ok(match value.kind {
@@ -412,9 +251,9 @@ pub fn to_termion_mouse(value: MouseEvent) -> Result<termion::event::MouseEvent,
         MouseEventKind::Down(MouseButton::Middle) => {
             termion::event::MouseEvent::Press(termion::event::MouseButton::Middle, column, row)
         }
-        val @ MouseEventKind::Down(MouseButton::Unknown) => {
-            Err(UnsupportedEvent(format!("{val:?}")))?
-        }
+        val @ MouseEventKind::Down(
+            MouseButton::Back | MouseButton::Forward | MouseButton::Other(_) | MouseButton::Unknown,
+        ) => Err(UnsupportedEvent(format!("{val:?}")))?,
         MouseEventKind::Up(_) => termion::event::MouseEvent::Release(column, row),
         MouseEventKind::Drag(_) => termion::event::MouseEvent::Hold(column, row),
         val @ MouseEventKind::Moved => Err(UnsupportedEvent(format!("{val:?}")))?,
@@ -1,7 +1,9 @@
+use std::time::Duration;
+
 use terminput::{
-    Event, KeyCode, KeyEvent, KeyEventKind, KeyEventState, KeyModifiers, MediaKeyCode,
-    ModifierDirection, ModifierKeyCode, MouseButton, MouseEvent, MouseEventKind, ScrollDirection,
-    UnsupportedEvent,
+    Backend, Event, KeyCode, KeyEvent, KeyEventState, KeyModifiers, MediaKeyCode,
+    ModifierDirection, ModifierKeyCode, MouseButton, MouseEvent, MouseEventKind, PositionUnit,
+    ScrollDirection, TimedEvent, UnsupportedEvent,
 };
 #[cfg(all(feature = "termwiz_0_22", not(feature = "termwiz_0_23")))]
 use termwiz_0_22 as termwiz;
@@ -26,6 +28,18 @@ pub fn to_terminput(value: termwiz::input::InputEvent) -> Result<Event, Unsuppor
     })
 }
 
+/// Converts the termwiz event to a terminput [`TimedEvent`] with a caller-supplied timestamp.
+pub fn to_terminput_timed(
+    value: termwiz::input::InputEvent,
+    timestamp: Duration,
+) -> Result<TimedEvent, UnsupportedEvent> {
+    Ok(TimedEvent::new(
+        to_terminput(value)?,
+        timestamp,
+        Backend::Termwiz.into(),
+    ))
+}
+
 /// Converts the terminput [`Event`] to a termwiz [`InputEvent`](termwiz::input::InputEvent).
 pub fn to_termwiz(value: Event) -> Result<termwiz::input::InputEvent, UnsupportedEvent> {
     Ok(match value {
@@ -38,7 +52,11 @@ pub fn to_termwiz(value: Event) -> Result<termwiz::input::InputEvent, Unsupporte
             cols: cols as usize,
             rows: rows as usize,
         },
-        Event::FocusGained | Event::FocusLost => Err(UnsupportedEvent(format!("{value:?}")))?,
+        Event::FocusGained
+        | Event::FocusLost
+        | Event::Text(_)
+        | Event::Reply(_)
+        | Event::Ime(_) => Err(UnsupportedEvent(format!("{value:?}")))?,
     })
 }
 
@@ -179,12 +197,9 @@ pub fn to_terminput_key(value: termwiz::input::KeyEvent) -> Result<KeyEvent, Uns
         termwiz::input::KeyCode::KeyPadBegin => (KeyCode::KeypadBegin, KeyEventState::KEYPAD),
         _ => Err(UnsupportedEvent(format!("{value:?}")))?,
     };
-    Ok(KeyEvent {
-        code,
-        modifiers: to_terminput_key_modifiers(value.modifiers),
-        kind: KeyEventKind::Press,
-        state,
-    })
+    Ok(KeyEvent::new(code)
+        .modifiers(to_terminput_key_modifiers(value.modifiers))
+        .state(state))
 }
 
 /// Converts the terminput [`KeyEvent`] to a termwiz [`KeyEvent`](termwiz::input::KeyEvent).
@@ -342,85 +357,83 @@ pub fn to_terminput_mouse(
         .mouse_buttons
         .contains(termwiz::input::MouseButtons::LEFT)
     {
-        return Ok(MouseEvent {
-            kind: MouseEventKind::Down(MouseButton::Left),
-            column: value.x - 1,
-            row: value.y - 1,
-            modifiers: to_terminput_key_modifiers(value.modifiers),
-        });
+        return Ok(MouseEvent::new(
+            MouseEventKind::Down(MouseButton::Left),
+            value.x - 1,
+            value.y - 1,
+        )
+        .modifiers(to_terminput_key_modifiers(value.modifiers)));
     }
     if value
         .mouse_buttons
         .contains(termwiz::input::MouseButtons::RIGHT)
     {
-        return Ok(MouseEvent {
-            kind: MouseEventKind::Down(MouseButton::Right),
-            column: value.x - 1,
-            row: value.y - 1,
-            modifiers: to_terminput_key_modifiers(value.modifiers),
-        });
+        return Ok(MouseEvent::new(
+            MouseEventKind::Down(MouseButton::Right),
+            value.x - 1,
+            value.y - 1,
+        )
+        .modifiers(to_terminput_key_modifiers(value.modifiers)));
     }
     if value
         .mouse_buttons
         .contains(termwiz::input::MouseButtons::MIDDLE)
     {
-        return Ok(MouseEvent {
-            kind: MouseEventKind::Down(MouseButton::Middle),
-            column: value.x - 1,
-            row: value.y - 1,
-            modifiers: to_terminput_key_modifiers(value.modifiers),
-        });
+        return Ok(MouseEvent::new(
+            MouseEventKind::Down(MouseButton::Middle),
+            value.x - 1,
+            value.y - 1,
+        )
+        .modifiers(to_terminput_key_modifiers(value.modifiers)));
     }
 
     if value.mouse_buttons.contains(
         termwiz::input::MouseButtons::VERT_WHEEL | termwiz::input::MouseButtons::WHEEL_POSITIVE,
     ) {
-        return Ok(MouseEvent {
-            kind: MouseEventKind::Scroll(ScrollDirection::Up),
-            column: value.x - 1,
-            row: value.y - 1,
-            modifiers: to_terminput_key_modifiers(value.modifiers),
-        });
+        return Ok(MouseEvent::new(
+            MouseEventKind::Scroll(ScrollDirection::Up),
+            value.x - 1,
+            value.y - 1,
+        )
+        .modifiers(to_terminput_key_modifiers(value.modifiers)));
     }
     if value
         .mouse_buttons
         .contains(termwiz::input::MouseButtons::VERT_WHEEL)
     {
-        return Ok(MouseEvent {
-            kind: MouseEventKind::Scroll(ScrollDirection::Down),
-            column: value.x - 1,
-            row: value.y - 1,
-            modifiers: to_terminput_key_modifiers(value.modifiers),
-        });
+        return Ok(MouseEvent::new(
+            MouseEventKind::Scroll(ScrollDirection::Down),
+            value.x - 1,
+            value.y - 1,
+        )
+        .modifiers(to_terminput_key_modifiers(value.modifiers)));
     }
     if value.mouse_buttons.contains(
         termwiz::input::MouseButtons::HORZ_WHEEL | termwiz::input::MouseButtons::WHEEL_POSITIVE,
     ) {
-        return Ok(MouseEvent {
-            kind: MouseEventKind::Scroll(ScrollDirection::Left),
-            column: value.x - 1,
-            row: value.y - 1,
-            modifiers: to_terminput_key_modifiers(value.modifiers),
-        });
+        return Ok(MouseEvent::new(
+            MouseEventKind::Scroll(ScrollDirection::Left),
+            value.x - 1,
+            value.y - 1,
+        )
+        .modifiers(to_terminput_key_modifiers(value.modifiers)));
     }
     if value
         .mouse_buttons
         .contains(termwiz::input::MouseButtons::HORZ_WHEEL)
     {
-        return Ok(MouseEvent {
-            kind: MouseEventKind::Scroll(ScrollDirection::Right),
-            column: value.x - 1,
-            row: value.y - 1,
-            modifiers: to_terminput_key_modifiers(value.modifiers),
-        });
+        return Ok(MouseEvent::new(
+            MouseEventKind::Scroll(ScrollDirection::Right),
+            value.x - 1,
+            value.y - 1,
+        )
+        .modifiers(to_terminput_key_modifiers(value.modifiers)));
     }
     if value.mouse_buttons == termwiz::input::MouseButtons::NONE {
-        return Ok(MouseEvent {
-            kind: MouseEventKind::Moved,
-            column: value.x - 1,
-            row: value.y - 1,
-            modifiers: to_terminput_key_modifiers(value.modifiers),
-        });
+        return Ok(
+            MouseEvent::new(MouseEventKind::Moved, value.x - 1, value.y - 1)
+                .modifiers(to_terminput_key_modifiers(value.modifiers)),
+        );
     }
 
     Err(UnsupportedEvent(format!("{value:?}")))
@@ -428,6 +441,9 @@ pub fn to_terminput_mouse(
 
 /// Converts the terminput [`MouseEvent`] to a termwiz [`MouseEvent`](termwiz::input::MouseEvent).
 pub fn mouse_to_termwiz(value: MouseEvent) -> Result<termwiz::input::MouseEvent, UnsupportedEvent> {
+    if value.unit == PositionUnit::Pixels {
+        return Err(UnsupportedEvent(format!("{value:?}")));
+    }
     Ok(match value.kind {
         MouseEventKind::Down(MouseButton::Left | MouseButton::Unknown) => {
             termwiz::input::MouseEvent {
@@ -449,9 +465,9 @@ pub fn mouse_to_termwiz(value: MouseEvent) -> Result<termwiz::input::MouseEvent,
             y: value.row + 1,
             modifiers: to_termwiz_key_modifiers(value.modifiers),
         },
-        MouseEventKind::Up(_) | MouseEventKind::Drag(_) => {
-            Err(UnsupportedEvent(format!("{value:?}")))?
-        }
+        MouseEventKind::Down(MouseButton::Back | MouseButton::Forward | MouseButton::Other(_))
+        | MouseEventKind::Up(_)
+        | MouseEventKind::Drag(_) => Err(UnsupportedEvent(format!("{value:?}")))?,
         MouseEventKind::Moved => termwiz::input::MouseEvent {
             mouse_buttons: termwiz::input::MouseButtons::NONE,
             x: value.column + 1,
@@ -1,6 +1,9 @@
+use std::time::Duration;
+
 use terminput::{
-    Event, KeyCode, KeyEvent, KeyEventKind, KeyEventState, KeyModifiers, MediaKeyCode,
-    ModifierDirection, ModifierKeyCode, MouseButton, MouseEvent, MouseEventKind, ScrollDirection,
+    Backend, CellGeometry, Event, ImeEvent, KeyCode, KeyEvent, KeyEventKind, KeyEventState,
+    KeyModifiers, MediaKeyCode, ModifierDirection, ModifierKeyCode, MouseButton, MouseEvent,
+    MouseEventKind, PhysicalKey, ScrollAmount, ScrollDirection, ScrollUnit, TimedEvent,
     UnsupportedEvent,
 };
 use web_sys::wasm_bindgen::JsValue;
@@ -8,17 +11,32 @@ use web_sys::wasm_bindgen::JsValue;
 use web_sys_0_3 as web_sys;
 
 /// Converts the [`web_sys`] [`MouseEvent`](web_sys::MouseEvent) to a terminput [`MouseEvent`].
+///
+/// The event's client coordinates are used as the cell position as-is. Use
+/// [`to_terminput_mouse_with_geometry`] to convert them from pixels.
 pub fn to_terminput_mouse(
     mouse_event: web_sys::MouseEvent,
+) -> Result<MouseEvent, UnsupportedEvent> {
+    to_terminput_mouse_with_geometry(mouse_event, &CellGeometry::default())
+}
+
+/// Converts the [`web_sys`] [`MouseEvent`](web_sys::MouseEvent) to a terminput [`MouseEvent`].
+///
+/// The event's client coordinates are converted to a cell position using the supplied
+/// [`CellGeometry`].
+pub fn to_terminput_mouse_with_geometry(
+    mouse_event: web_sys::MouseEvent,
+    geometry: &CellGeometry,
 ) -> Result<MouseEvent, UnsupportedEvent> {
     let event_kind = mouse_event.type_();
     let mouse_button = to_terminput_mouse_button(mouse_event.button());
-    Ok(MouseEvent {
-        kind: to_terminput_mouse_kind(event_kind.as_str(), mouse_button)?,
-        modifiers: to_terminput_mouse_modifiers(&mouse_event),
-        column: mouse_event.client_x() as u16,
-        row: mouse_event.client_y() as u16,
-    })
+    let (column, row) = to_terminput_cell(mouse_event.client_x(), mouse_event.client_y(), geometry);
+    Ok(MouseEvent::new(
+        to_terminput_mouse_kind(event_kind.as_str(), mouse_button)?,
+        column,
+        row,
+    )
+    .modifiers(to_terminput_mouse_modifiers(&mouse_event)))
 }
 
 /// Converts the [`web_sys`] [`DragEvent`](web_sys::DragEvent) to a terminput [`MouseEvent`].
@@ -26,39 +44,63 @@ pub fn to_terminput_mouse(
 /// **NOTE:** this should be used with the `dragover` event to ensure the row/column values are
 /// populated.
 pub fn to_terminput_mouse_drag(drag_event: web_sys::DragEvent) -> MouseEvent {
+    to_terminput_mouse_drag_with_geometry(drag_event, &CellGeometry::default())
+}
+
+/// Converts the [`web_sys`] [`DragEvent`](web_sys::DragEvent) to a terminput [`MouseEvent`],
+/// converting the client coordinates using the supplied [`CellGeometry`].
+pub fn to_terminput_mouse_drag_with_geometry(
+    drag_event: web_sys::DragEvent,
+    geometry: &CellGeometry,
+) -> MouseEvent {
     let mouse_button = to_terminput_mouse_button(drag_event.button());
+    let (column, row) = to_terminput_cell(drag_event.client_x(), drag_event.client_y(), geometry);
 
-    MouseEvent {
-        kind: MouseEventKind::Drag(mouse_button),
-        modifiers: to_terminput_drag_modifiers(&drag_event),
-        column: drag_event.client_x() as u16,
-        row: drag_event.client_y() as u16,
-    }
+    MouseEvent::new(MouseEventKind::Drag(mouse_button), column, row)
+        .modifiers(to_terminput_drag_modifiers(&drag_event))
 }
 
 /// Converts the [`web_sys`] [`WheelEvent`](`web_sys::WheelEvent`) to a terminput [`MouseEvent`].
 pub fn to_terminput_mouse_scroll(event: web_sys::WheelEvent) -> MouseEvent {
+    to_terminput_mouse_scroll_with_geometry(event, &CellGeometry::default())
+}
+
+/// Converts the [`web_sys`] [`WheelEvent`](`web_sys::WheelEvent`) to a terminput [`MouseEvent`],
+/// converting the client coordinates using the supplied [`CellGeometry`].
+pub fn to_terminput_mouse_scroll_with_geometry(
+    event: web_sys::WheelEvent,
+    geometry: &CellGeometry,
+) -> MouseEvent {
     let direction = to_terminput_scroll_direction(&event);
-    MouseEvent {
-        kind: MouseEventKind::Scroll(direction),
-        modifiers: KeyModifiers::empty(),
-        column: event.client_x() as u16,
-        row: event.client_y() as u16,
-    }
+    let (column, row) = to_terminput_cell(event.client_x(), event.client_y(), geometry);
+    MouseEvent::new(MouseEventKind::Scroll(direction), column, row)
+        .scroll_amount(to_terminput_scroll_amount(&event))
+}
+
+/// Wraps the terminput event converted from the [`web_sys`] [`Event`](web_sys::Event) in a
+/// [`TimedEvent`], using the DOM event's timestamp.
+///
+/// The timestamp is relative to the time origin of the page.
+pub fn to_terminput_timed(event: impl Into<Event>, source: &web_sys::Event) -> TimedEvent {
+    TimedEvent::new(
+        event,
+        Duration::from_secs_f64(source.time_stamp().max(0.0) / 1000.0),
+        Backend::WebSys.into(),
+    )
 }
 
 /// Converts the [`web_sys`] [`KeyboardEvent`](`web_sys::KeyboardEvent`) to a terminput
 /// [`KeyEvent`].
 pub fn to_terminput_key(key_event: web_sys::KeyboardEvent) -> Result<KeyEvent, UnsupportedEvent> {
-    Ok(KeyEvent {
-        code: to_terminput_key_code(
-            &key_event.key(),
-            to_terminput_modifier_direction(key_event.location()),
-        )?,
-        modifiers: to_terminput_key_modifiers(&key_event),
-        state: key_state_to_terminput(&key_event),
-        kind: to_terminput_key_kind(&key_event),
-    })
+    let mut mapped = KeyEvent::new(to_terminput_key_code(
+        &key_event.key(),
+        to_terminput_modifier_direction(key_event.location()),
+    )?)
+    .modifiers(to_terminput_key_modifiers(&key_event))
+    .state(key_state_to_terminput(&key_event))
+    .kind(to_terminput_key_kind(&key_event));
+    mapped.physical_key = PhysicalKey::from_code(&key_event.code());
+    Ok(mapped)
 }
 
 /// Converts the [`web_sys`] [`ClipboardEvent`](`web_sys::ClipboardEvent`) to a terminput paste
@@ -76,6 +118,50 @@ pub fn to_terminput_paste(
     }
 }
 
+/// Converts the [`web_sys`] [`InputEvent`](`web_sys::InputEvent`) to a terminput text [`Event`].
+///
+/// This should be used with the `beforeinput` or `input` events. Only text insertions are
+/// supported.
+pub fn to_terminput_text(input_event: web_sys::InputEvent) -> Result<Event, UnsupportedEvent> {
+    match (input_event.input_type().as_str(), input_event.data()) {
+        ("insertText" | "insertReplacementText" | "insertCompositionText", Some(data)) => {
+            Ok(Event::Text(data))
+        }
+        (input_type, _) => Err(UnsupportedEvent(input_type.to_string())),
+    }
+}
+
+/// Converts the terminput text [`Event`] to a [`web_sys`] `input`
+/// [`InputEvent`](`web_sys::InputEvent`).
+pub fn to_web_sys_text(event: Event) -> Result<web_sys::InputEvent, UnsupportedEvent> {
+    let Event::Text(text) = event else {
+        return Err(UnsupportedEvent(format!("{event:?}")));
+    };
+    let init = web_sys::InputEventInit::new();
+    init.set_data(Some(&text));
+    init.set_input_type("insertText");
+    web_sys::InputEvent::new_with_event_init_dict("input", &init)
+        .map_err(|e| map_js_error("failed to create input event: ", e))
+}
+
+/// Converts the [`web_sys`] [`CompositionEvent`](`web_sys::CompositionEvent`) to a terminput IME
+/// [`Event`].
+///
+/// This should be used with the `compositionstart`, `compositionupdate`, and `compositionend`
+/// events. The browser doesn't report when the IME is disabled, so the end of a composition is
+/// mapped to [`ImeEvent::Commit`].
+pub fn to_terminput_composition(
+    composition_event: web_sys::CompositionEvent,
+) -> Result<Event, UnsupportedEvent> {
+    let data = composition_event.data().unwrap_or_default();
+    Ok(Event::Ime(match composition_event.type_().as_str() {
+        "compositionstart" => ImeEvent::Enabled,
+        "compositionupdate" => ImeEvent::preedit(data),
+        "compositionend" => ImeEvent::Commit(data),
+        kind => return Err(UnsupportedEvent(kind.to_string())),
+    }))
+}
+
 /// Converts the [`web_sys`] window size to a terminput resize [`Event`].
 pub fn to_terminput_resize(window: &web_sys::Window) -> Result<Event, UnsupportedEvent> {
     let height = window
@@ -108,6 +194,24 @@ fn to_terminput_scroll_direction(event: &web_sys::WheelEvent) -> ScrollDirection
     }
 }
 
+fn to_terminput_cell(client_x: i32, client_y: i32, geometry: &CellGeometry) -> (u16, u16) {
+    geometry.to_cell(client_x as f32, client_y as f32)
+}
+
+fn to_terminput_scroll_amount(event: &web_sys::WheelEvent) -> ScrollAmount {
+    // The DOM reports positive deltas when scrolling down or right, which is the opposite of
+    // terminput's convention.
+    ScrollAmount::new(
+        -event.delta_x() as f32,
+        -event.delta_y() as f32,
+        match event.delta_mode() {
+            web_sys::WheelEvent::DOM_DELTA_LINE => ScrollUnit::Lines,
+            web_sys::WheelEvent::DOM_DELTA_PAGE => ScrollUnit::Pages,
+            _ => ScrollUnit::Pixels,
+        },
+    )
+}
+
 fn to_terminput_mouse_kind(
     event_kind: &str,
     mouse_button: MouseButton,
@@ -125,6 +229,10 @@ fn to_terminput_mouse_button(button: i16) -> MouseButton {
         0 => MouseButton::Left,
         1 => MouseButton::Middle,
         2 => MouseButton::Right,
+        3 => MouseButton::Back,
+        4 => MouseButton::Forward,
+        // Continue the X11 numbering, where the back and forward buttons are 8 and 9
+        n @ 5..=250 => MouseButton::Other(n as u8 + 5),
         _ => MouseButton::Unknown,
     }
 }
@@ -275,3 +383,52 @@ fn to_terminput_key_code(
         key => return Err(UnsupportedEvent(key.to_string())),
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fractional_scale_factor() {
+        let geometry = CellGeometry::new(9.0, 18.0)
+            .origin(10.0, 20.0)
+            .scale_factor(1.5);
+        // (30, 50) is (30, 45) physical pixels from the origin
+        assert_eq!(to_terminput_cell(30, 50, &geometry), (3, 2));
+        assert_eq!(to_terminput_cell(15, 31, &geometry), (0, 0));
+        assert_eq!(to_terminput_cell(16, 32, &geometry), (1, 1));
+        // Positions above or to the left of the widget are clamped
+        assert_eq!(to_terminput_cell(0, 0, &geometry), (0, 0));
+        // Without a geometry, client coordinates are used as-is
+        assert_eq!(
+            to_terminput_cell(30, 50, &CellGeometry::default()),
+            (30, 50)
+        );
+    }
+
+    #[test]
+    fn test_physical_key() {
+        // Keys that are in different places on AZERTY and QWERTZ layouts
+        for (key, code, key_code, physical_key) in [
+            ("q", "KeyA", KeyCode::Char('q'), PhysicalKey::KeyA),
+            ("a", "KeyQ", KeyCode::Char('a'), PhysicalKey::KeyQ),
+            ("z", "KeyW", KeyCode::Char('z'), PhysicalKey::KeyW),
+            ("m", "Semicolon", KeyCode::Char('m'), PhysicalKey::Semicolon),
+            ("z", "KeyY", KeyCode::Char('z'), PhysicalKey::KeyY),
+            (
+                "Shift",
+                "ShiftRight",
+                KeyCode::Modifier(ModifierKeyCode::Shift, ModifierDirection::Right),
+                PhysicalKey::ShiftRight,
+            ),
+        ] {
+            assert_eq!(
+                to_terminput_key_code(key, to_terminput_modifier_direction(2)).ok(),
+                Some(key_code)
+            );
+            assert_eq!(PhysicalKey::from_code(code), Some(physical_key));
+            assert_eq!(physical_key.code(), code);
+        }
+        assert_eq!(PhysicalKey::from_code("Unidentified"), None);
+    }
+}
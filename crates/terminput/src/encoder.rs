@@ -3,34 +3,11 @@ use std::format;
 use std::io::{self, Cursor, Seek, Write};
 use std::string::{String, ToString};
 
-use bitflags::bitflags;
-
 use crate::{
-    Event, KeyCode, KeyEvent, KeyEventKind, KeyEventState, KeyModifiers, MediaKeyCode,
+    Event, KeyCode, KeyEvent, KeyEventKind, KeyEventState, KeyModifiers, KittyFlags, MediaKeyCode,
     ModifierDirection, ModifierKeyCode, MouseButton, MouseEvent, MouseEventKind, ScrollDirection,
 };
 
-bitflags! {
-    /// Controls which keyboard enhancement flags will be considered during encoding.
-    /// These flags are described in Kitty's documentation on [progressive enhancement](https://sw.kovidgoyal.net/kitty/keyboard-protocol/#progressive-enhancement).
-    #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
-    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(transparent))]
-    pub struct KittyFlags: u8 {
-        /// Represent escape and modified keys using CSI-u sequences.
-        const DISAMBIGUATE_ESCAPE_CODES = 1<<1;
-        /// Report release and repeat events.
-        const REPORT_EVENT_TYPES = 1<<2;
-        /// Send [alternate keycodes](https://sw.kovidgoyal.net/kitty/keyboard-protocol/#key-codes)
-        /// in addition to the base keycode. The encoder currently only supports this for
-        /// alphabetic keys since applying this to other types of keys requires knowledge of the
-        /// keyboard layout used to type the key.
-        const REPORT_ALTERNATE_KEYS = 1<<3;
-        /// Represent all keyboard events as CSI-u sequences.
-        const REPORT_ALL_KEYS_AS_ESCAPE_CODES = 1<<4;
-
-    }
-}
-
 /// Encoding protocol used to control the output of [`Event::encode`]
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -39,6 +16,98 @@ pub enum Encoding {
     Xterm,
     /// Encode using the Kitty protocol.
     Kitty(KittyFlags),
+    /// Encode using xterm's `modifyOtherKeys` extension to the legacy protocol.
+    ///
+    /// Modified keys are sent as `CSI 27 ; modifiers ; code ~`. At level 1, this form is only used
+    /// for keys that the legacy protocol can't represent, such as Ctrl+Enter. At level 2, it's
+    /// used for all modified keys except Shift with a printable character, so Ctrl+I can be told
+    /// apart from Tab. Level 0 is equivalent to [`Encoding::Xterm`].
+    ModifyOtherKeys {
+        /// The `modifyOtherKeys` level.
+        level: u8,
+    },
+    /// Encode using the legacy Xterm protocol with the supplied [`XtermOptions`].
+    XtermWith(XtermOptions),
+}
+
+/// Options for the legacy Xterm protocol, used with [`Encoding::XtermWith`].
+///
+/// These correspond to terminal settings that change the bytes sent for some keys. The default
+/// options are equivalent to [`Encoding::Xterm`].
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct XtermOptions {
+    /// Send Alt with a single-byte key by setting the high bit of the byte instead of prefixing it
+    /// with ESC, like xterm's `eightBitInput` resource. Keys that are encoded as escape sequences
+    /// still use the ESC prefix.
+    pub eight_bit_meta: bool,
+    /// Send unmodified keys with [`KeyEventState::KEYPAD`] as `SS3` sequences (`ESC O p` for
+    /// keypad 0 and so on), like a terminal that has keypad application mode (DECKPAM) enabled.
+    pub application_keypad: bool,
+    /// Send unmodified arrow keys, Home and End as `SS3` sequences (`ESC O A` for Up and so on),
+    /// like a terminal that has application cursor key mode (DECCKM) enabled. Modified keys are
+    /// still sent as `CSI 1 ; modifiers A`, which is what xterm does in either mode.
+    pub application_cursor_keys: bool,
+    /// The family of sequences used for special keys such as arrows, Home, End and function keys.
+    pub family: KeySequenceFamily,
+}
+
+impl XtermOptions {
+    /// Creates a new [`XtermOptions`] with the default settings.
+    pub const fn new() -> Self {
+        Self {
+            eight_bit_meta: false,
+            application_keypad: false,
+            application_cursor_keys: false,
+            family: KeySequenceFamily::Xterm,
+        }
+    }
+
+    /// Sets whether Alt is sent by setting the high bit.
+    pub const fn eight_bit_meta(mut self, eight_bit_meta: bool) -> Self {
+        self.eight_bit_meta = eight_bit_meta;
+        self
+    }
+
+    /// Sets whether keypad keys are sent using keypad application mode.
+    pub const fn application_keypad(mut self, application_keypad: bool) -> Self {
+        self.application_keypad = application_keypad;
+        self
+    }
+
+    /// Sets whether cursor keys are sent using application cursor key mode.
+    pub const fn application_cursor_keys(mut self, application_cursor_keys: bool) -> Self {
+        self.application_cursor_keys = application_cursor_keys;
+        self
+    }
+
+    /// Sets the family of sequences used for special keys.
+    pub const fn family(mut self, family: KeySequenceFamily) -> Self {
+        self.family = family;
+        self
+    }
+}
+
+/// Families of sequences that terminals send for special keys, used with [`XtermOptions::family`].
+///
+/// Keys that are sent the same way as xterm, such as characters, are unaffected.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum KeySequenceFamily {
+    /// Sequences sent by xterm and most modern terminals.
+    #[default]
+    Xterm,
+    /// Sequences sent by rxvt and urxvt. Modifiers are sent as the final byte of special keys
+    /// (`ESC [2$` for Shift+Insert, `ESC [5^` for Ctrl+PageUp and `ESC [7@` for Ctrl+Shift+Home)
+    /// and as lowercase final bytes for arrow keys (`ESC [a` for Shift+Up and `ESC O a` for
+    /// Ctrl+Up). Alt is sent as an ESC prefix.
+    Rxvt,
+    /// Sequences sent by the Linux console, which uses `ESC [[A` through `ESC [[E` for F1-F5 and
+    /// `ESC [1~` and `ESC [4~` for Home and End. Alt is sent as an ESC prefix and other modifiers
+    /// can't be sent with special keys.
+    LinuxConsole,
+    /// Sequences sent by GNU screen and tmux, which use `ESC [1~` and `ESC [4~` for Home and End.
+    Screen,
 }
 
 fn unsupported_error<T>(event: T) -> io::Result<usize>
@@ -56,7 +125,8 @@ impl Event {
     /// Returns the number of bytes written, following the semantics of [`std::io::Write::write`].
     ///
     /// The supplied buffer needs enough space to hold the encoded sequence. If you're unsure of
-    /// how large the result will be, 16 bytes is more than sufficient.
+    /// how large the result will be, 16 bytes is more than sufficient for most events. Key events
+    /// with associated text may need up to 128 bytes.
     ///
     /// # Example
     ///
@@ -74,9 +144,60 @@ impl Event {
         match encoding {
             Encoding::Xterm => self.to_escape_sequence(buf),
             Encoding::Kitty(flags) => self.to_kitty_escape_sequence(buf, flags),
+            Encoding::ModifyOtherKeys { level } => {
+                self.to_modify_other_keys_escape_sequence(buf, level)
+            }
+            Encoding::XtermWith(options) => self.to_xterm_escape_sequence(buf, options),
         }
     }
 
+    fn to_xterm_escape_sequence(&self, buf: &mut [u8], options: XtermOptions) -> io::Result<usize> {
+        if let Self::Key(key_event) = self
+            && options.application_keypad
+            && key_event.kind == KeyEventKind::Press
+            && key_event.state.contains(KeyEventState::KEYPAD)
+            && key_event.modifiers.is_empty()
+            && let Some(final_byte) = keypad_final_byte(key_event.code)
+        {
+            let mut buf = Cursor::new(buf);
+            buf.write_all(&[b'\x1B', b'O', final_byte])?;
+            return Ok(buf.position() as usize);
+        }
+        if let Self::Key(key_event) = self
+            && options.application_cursor_keys
+            && key_event.kind == KeyEventKind::Press
+            && key_event.modifiers.is_empty()
+            && let Some(final_byte) = cursor_key_final_byte(key_event.code)
+        {
+            let mut buf = Cursor::new(buf);
+            buf.write_all(&[b'\x1B', b'O', final_byte])?;
+            return Ok(buf.position() as usize);
+        }
+        if let Self::Key(key_event) = self
+            && key_event.kind == KeyEventKind::Press
+            && let Some(written) = encode_family_key_event(key_event, options.family, buf)?
+        {
+            return Ok(written);
+        }
+        if let Self::Key(key_event) = self
+            && options.eight_bit_meta
+            && key_event.modifiers.intersects(KeyModifiers::ALT)
+        {
+            let mut unmodified = *key_event;
+            unmodified.modifiers.remove(KeyModifiers::ALT);
+            let mut byte = [0; 1];
+            // Only keys that are sent as a single 7-bit byte have room for the meta bit
+            if let Ok(1) = Self::Key(unmodified).to_escape_sequence(&mut byte)
+                && byte[0] < 0x80
+            {
+                let mut buf = Cursor::new(buf);
+                buf.write_all(&[byte[0] | 0x80])?;
+                return Ok(buf.position() as usize);
+            }
+        }
+        self.to_escape_sequence(buf)
+    }
+
     fn to_escape_sequence(&self, buf: &mut [u8]) -> io::Result<usize> {
         let mut buf = Cursor::new(buf);
         match self {
@@ -96,7 +217,22 @@ impl Event {
                 buf.write_all(b"\x1B[201~")?;
                 Ok(buf.position() as usize)
             }
-            Self::Resize { .. } => unsupported_error("Resize"),
+            Self::Text(text) => {
+                // Terminals send typed text as-is
+                buf.write_all(text.as_bytes())?;
+                Ok(buf.position() as usize)
+            }
+            Self::Resize { rows, cols } => {
+                // In-band resize notification (mode 2048). The pixel dimensions aren't known, so
+                // they're reported as 0.
+                buf.write_all(b"\x1B[48;")?;
+                buf.write_all(rows.to_string().as_bytes())?;
+                buf.write_all(b";")?;
+                buf.write_all(cols.to_string().as_bytes())?;
+                buf.write_all(b";0;0t")?;
+                Ok(buf.position() as usize)
+            }
+            Self::Reply(_) | Self::Ime(_) => unsupported_error(self),
         }
     }
 
@@ -107,6 +243,32 @@ impl Event {
         }
     }
 
+    fn to_modify_other_keys_escape_sequence(&self, buf: &mut [u8], level: u8) -> io::Result<usize> {
+        let Self::Key(key_event) = self else {
+            return self.to_escape_sequence(buf);
+        };
+        let key_event = key_event.normalize_case();
+        if key_event.kind != KeyEventKind::Press || !is_modify_other_keys(key_event, level) {
+            return self.to_escape_sequence(buf);
+        }
+
+        let codepoint = match key_event.code {
+            KeyCode::Char(c) => c as u32,
+            KeyCode::Tab => 9,
+            KeyCode::Enter => 13,
+            KeyCode::Esc => 27,
+            KeyCode::Backspace => 127,
+            _ => return self.to_escape_sequence(buf),
+        };
+        let mut buf = Cursor::new(buf);
+        buf.write_all(b"\x1B[27;")?;
+        buf.write_all((key_event.modifiers.bits() + 1).to_string().as_bytes())?;
+        buf.write_all(b";")?;
+        buf.write_all(codepoint.to_string().as_bytes())?;
+        buf.write_all(b"~")?;
+        Ok(buf.position() as usize)
+    }
+
     fn encode_kitty_key_event(
         &self,
         buf: &mut [u8],
@@ -135,7 +297,14 @@ impl Event {
             return self.to_escape_sequence(buf);
         }
 
-        let key_event = key_event.normalize_case();
+        let mut key_event = key_event.normalize_case();
+        // The parser replaces the key code with the shifted key, so the shift modifier needs to be
+        // restored
+        if let (KeyCode::Char(c), Some(shifted_key)) = (key_event.code, key_event.shifted_key)
+            && c == shifted_key
+        {
+            key_event.modifiers.insert(KeyModifiers::SHIFT);
+        }
         let mut buf = Cursor::new(buf);
         buf.write_all(b"\x1B[")?;
         let mut trailing_char = b'u';
@@ -194,6 +363,141 @@ impl Event {
     }
 }
 
+/// Returns the final byte of the `SS3` sequence sent by the numeric keypad in application mode.
+fn keypad_final_byte(key_code: KeyCode) -> Option<u8> {
+    Some(match key_code {
+        KeyCode::Char(c @ '0'..='9') => c as u8 - b'0' + b'p',
+        KeyCode::Enter => b'M',
+        KeyCode::Tab => b'I',
+        KeyCode::Char(' ') => b' ',
+        KeyCode::Char('=') => b'X',
+        KeyCode::Char('*') => b'j',
+        KeyCode::Char('+') => b'k',
+        KeyCode::Char(',') => b'l',
+        KeyCode::Char('-') => b'm',
+        KeyCode::Char('.') => b'n',
+        KeyCode::Char('/') => b'o',
+        _ => return None,
+    })
+}
+
+/// Returns the final byte of the sequence sent by a cursor key, which is the same in both normal
+/// and application cursor key mode.
+fn cursor_key_final_byte(key_code: KeyCode) -> Option<u8> {
+    Some(match key_code {
+        KeyCode::Up => b'A',
+        KeyCode::Down => b'B',
+        KeyCode::Right => b'C',
+        KeyCode::Left => b'D',
+        KeyCode::Home => b'H',
+        KeyCode::End => b'F',
+        _ => return None,
+    })
+}
+
+/// Encodes the special keys that the given family sends differently from xterm. Returns `None` for
+/// keys that are sent the same way.
+fn encode_family_key_event(
+    key_event: &KeyEvent,
+    family: KeySequenceFamily,
+    buf: &mut [u8],
+) -> io::Result<Option<usize>> {
+    let mut buf = Cursor::new(buf);
+    let is_alt = key_event.modifiers.intersects(KeyModifiers::ALT);
+    let modifiers = key_event.modifiers.difference(KeyModifiers::ALT);
+    match family {
+        KeySequenceFamily::Xterm => return Ok(None),
+        KeySequenceFamily::Rxvt => {
+            if matches!(
+                key_event.code,
+                KeyCode::Up | KeyCode::Down | KeyCode::Right | KeyCode::Left
+            ) && let Some(final_byte) = cursor_key_final_byte(key_event.code)
+            {
+                let sequence = match modifiers {
+                    KeyModifiers::NONE => [b'\x1B', b'[', final_byte],
+                    KeyModifiers::SHIFT => [b'\x1B', b'[', final_byte.to_ascii_lowercase()],
+                    KeyModifiers::CTRL => [b'\x1B', b'O', final_byte.to_ascii_lowercase()],
+                    _ => return unsupported_error(key_event).map(Some),
+                };
+                if is_alt {
+                    buf.write_all(b"\x1B")?;
+                }
+                buf.write_all(&sequence)?;
+                return Ok(Some(buf.position() as usize));
+            }
+            let number: u8 = match key_event.code {
+                KeyCode::Insert => 2,
+                KeyCode::Delete => 3,
+                KeyCode::PageUp => 5,
+                KeyCode::PageDown => 6,
+                KeyCode::Home => 7,
+                KeyCode::End => 8,
+                KeyCode::F(n @ 1..=5) => n + 10,
+                KeyCode::F(n @ 6..=10) => n + 11,
+                KeyCode::F(n @ 11..=12) => n + 12,
+                _ => return Ok(None),
+            };
+            let suffix = match modifiers {
+                KeyModifiers::NONE => b'~',
+                KeyModifiers::SHIFT => b'$',
+                KeyModifiers::CTRL => b'^',
+                m if m == KeyModifiers::CTRL | KeyModifiers::SHIFT => b'@',
+                _ => return unsupported_error(key_event).map(Some),
+            };
+            if is_alt {
+                buf.write_all(b"\x1B")?;
+            }
+            buf.write_all(b"\x1B[")?;
+            buf.write_all(number.to_string().as_bytes())?;
+            buf.write_all(&[suffix])?;
+        }
+        KeySequenceFamily::LinuxConsole => {
+            if !matches!(
+                key_event.code,
+                KeyCode::Left
+                    | KeyCode::Right
+                    | KeyCode::Up
+                    | KeyCode::Down
+                    | KeyCode::Home
+                    | KeyCode::End
+                    | KeyCode::PageUp
+                    | KeyCode::PageDown
+                    | KeyCode::Delete
+                    | KeyCode::Insert
+                    | KeyCode::F(_)
+            ) {
+                return Ok(None);
+            }
+            if !modifiers.is_empty() {
+                return unsupported_error(key_event).map(Some);
+            }
+            if is_alt {
+                buf.write_all(b"\x1B")?;
+            }
+            match key_event.code {
+                KeyCode::F(n @ 1..=5) => buf.write_all(&[b'\x1B', b'[', b'[', b'A' + n - 1])?,
+                KeyCode::Home => buf.write_all(b"\x1B[1~")?,
+                KeyCode::End => buf.write_all(b"\x1B[4~")?,
+                key_code => return encode_key_event(&KeyEvent::new(key_code), &mut buf).map(Some),
+            }
+        }
+        KeySequenceFamily::Screen => {
+            let number = match key_event.code {
+                KeyCode::Home => b'1',
+                KeyCode::End => b'4',
+                _ => return Ok(None),
+            };
+            buf.write_all(&[b'\x1B', b'[', number])?;
+            if !key_event.modifiers.is_empty() {
+                buf.write_all(b";")?;
+                buf.write_all((key_event.modifiers.bits() + 1).to_string().as_bytes())?;
+            }
+            buf.write_all(b"~")?;
+        }
+    }
+    Ok(Some(buf.position() as usize))
+}
+
 fn encode_key_event(key_event: &KeyEvent, buf: &mut Cursor<&mut [u8]>) -> io::Result<usize> {
     let key_event = key_event.normalize_case();
     if key_event.kind != KeyEventKind::Press {
@@ -297,16 +601,64 @@ fn encode_key_event(key_event: &KeyEvent, buf: &mut Cursor<&mut [u8]>) -> io::Re
     Ok(buf.position() as usize)
 }
 
+fn is_modify_other_keys(key_event: KeyEvent, level: u8) -> bool {
+    // Alt is sent as an escape prefix in the legacy protocol, so it doesn't need to be encoded
+    let modifiers = key_event.modifiers.difference(KeyModifiers::ALT);
+    match level {
+        0 => false,
+        1 => match key_event.code {
+            KeyCode::Char(c) => {
+                !(modifiers.is_empty()
+                    || modifiers == KeyModifiers::SHIFT
+                    || (modifiers == KeyModifiers::CTRL
+                        && (c.is_ascii_lowercase() || matches!(c, ' ' | '4'..='7'))))
+            }
+            KeyCode::Tab => !(modifiers.is_empty() || modifiers == KeyModifiers::SHIFT),
+            KeyCode::Backspace => !(modifiers.is_empty() || modifiers == KeyModifiers::CTRL),
+            KeyCode::Enter | KeyCode::Esc => !modifiers.is_empty(),
+            _ => false,
+        },
+        _ => match key_event.code {
+            KeyCode::Char(_) => !key_event
+                .modifiers
+                .difference(KeyModifiers::SHIFT)
+                .is_empty(),
+            // Shift+Tab keeps its legacy CSI Z form
+            KeyCode::Tab => !key_event.modifiers.is_empty() && modifiers != KeyModifiers::SHIFT,
+            KeyCode::Enter | KeyCode::Esc | KeyCode::Backspace => !key_event.modifiers.is_empty(),
+            _ => false,
+        },
+    }
+}
+
+fn mouse_button_code(button: MouseButton) -> Option<u8> {
+    match button {
+        MouseButton::Left | MouseButton::Unknown => Some(0),
+        MouseButton::Middle => Some(1),
+        MouseButton::Right => Some(2),
+        // Buttons 8 through 11 are encoded with the high bit set
+        MouseButton::Back | MouseButton::Other(8) => Some(128),
+        MouseButton::Forward | MouseButton::Other(9) => Some(129),
+        MouseButton::Other(n @ 10..=11) => Some(120 + n),
+        MouseButton::Other(_) => None,
+    }
+}
+
 fn encode_mouse_event(mouse_event: &MouseEvent, buf: &mut Cursor<&mut [u8]>) -> io::Result<usize> {
     let mut base = match mouse_event.kind {
         MouseEventKind::Moved => 35,
-        MouseEventKind::Down(MouseButton::Left | MouseButton::Unknown)
-        | MouseEventKind::Up(MouseButton::Left | MouseButton::Unknown) => 0,
-        MouseEventKind::Down(MouseButton::Middle) | MouseEventKind::Up(MouseButton::Middle) => 1,
-        MouseEventKind::Down(MouseButton::Right) | MouseEventKind::Up(MouseButton::Right) => 2,
-        MouseEventKind::Drag(MouseButton::Left | MouseButton::Unknown) => 32,
-        MouseEventKind::Drag(MouseButton::Middle) => 33,
-        MouseEventKind::Drag(MouseButton::Right) => 34,
+        MouseEventKind::Down(button) | MouseEventKind::Up(button) => {
+            let Some(code) = mouse_button_code(button) else {
+                return unsupported_error(button);
+            };
+            code
+        }
+        MouseEventKind::Drag(button) => {
+            let Some(code) = mouse_button_code(button) else {
+                return unsupported_error(button);
+            };
+            code + 32
+        }
         MouseEventKind::Scroll(ScrollDirection::Down) => 65,
         MouseEventKind::Scroll(ScrollDirection::Up) => 64,
         MouseEventKind::Scroll(ScrollDirection::Left) => 66,
@@ -321,6 +673,8 @@ fn encode_mouse_event(mouse_event: &MouseEvent, buf: &mut Cursor<&mut [u8]>) ->
     if mouse_event.modifiers.intersects(KeyModifiers::CTRL) {
         base += 16;
     }
+    // SGR-Pixels (mode 1016) uses the same encoding as SGR, so pixel positions don't need any
+    // special handling
     buf.write_all(b"\x1B[<")?;
     buf.write_all(base.to_string().as_bytes())?;
     buf.write_all(b";")?;
@@ -347,10 +701,19 @@ fn write_kitty_modifiers(
     let extra_modifiers = key_event
         .state
         .intersection(KeyEventState::CAPS_LOCK | KeyEventState::NUM_LOCK);
+    // Text is only sent for key events that use the CSI-u form
+    let text = key_event.text.filter(|_| {
+        trailing_char == b'u'
+            && key_event.kind != KeyEventKind::Release
+            && flags.contains(
+                KittyFlags::REPORT_ALL_KEYS_AS_ESCAPE_CODES | KittyFlags::REPORT_ASSOCIATED_TEXT,
+            )
+    });
 
     if !key_event.modifiers.is_empty()
         || !extra_modifiers.is_empty()
         || (key_event.kind != KeyEventKind::Press && report_event_types)
+        || text.is_some()
     {
         buf.write_all(b";")?;
         let modifier_sum = key_event.modifiers.bits() + (extra_modifiers.bits() << 5) + 1;
@@ -367,6 +730,12 @@ fn write_kitty_modifiers(
             KeyEventKind::Press => {}
         };
     }
+    if let Some(text) = text {
+        for (i, c) in text.as_str().chars().enumerate() {
+            buf.write_all(if i == 0 { b";" } else { b":" })?;
+            buf.write_all(&(c as u32).to_string().into_bytes())?;
+        }
+    }
     buf.write_all(&[trailing_char])?;
     Ok(())
 }
@@ -543,17 +912,29 @@ fn write_kitty_encoding(
         }
         KeyCode::Char(c) => {
             // We should always use the lower-cased key for the first value
-            let c = c.to_ascii_lowercase();
+            let c = match key_event.unshifted_key {
+                Some(unshifted_key) => unshifted_key,
+                None if key_event.shifted_key == Some(c) => unshifted_char(c),
+                None => c.to_ascii_lowercase(),
+            };
             convert_suffix_code(KeyCode::Char(c), key_event.modifiers, buf)?;
-            if flags.intersects(KittyFlags::REPORT_ALTERNATE_KEYS)
-                && key_event.modifiers.intersects(KeyModifiers::SHIFT)
-            {
-                // Ideally we could do this for other chars besides just ascii,
-                // but that requires knowing the keyboard layout
-                let upper = c.to_ascii_uppercase();
-                if upper != c {
+            if flags.intersects(KittyFlags::REPORT_ALTERNATE_KEYS) {
+                // If the shifted key wasn't supplied, we can only infer it for ascii chars since
+                // other chars require knowing the keyboard layout
+                let shifted_key = key_event
+                    .shifted_key
+                    .or_else(|| Some(c.to_ascii_uppercase()).filter(|upper| *upper != c))
+                    .filter(|_| key_event.modifiers.intersects(KeyModifiers::SHIFT));
+                if let Some(shifted_key) = shifted_key {
+                    buf.write_all(b":")?;
+                    buf.write_all(&(shifted_key as u32).to_string().into_bytes())?;
+                }
+                if let Some(base_layout_key) = key_event.base_layout_key {
+                    if shifted_key.is_none() {
+                        buf.write_all(b":")?;
+                    }
                     buf.write_all(b":")?;
-                    buf.write_all(&(upper as u8).to_string().into_bytes())?;
+                    buf.write_all(&(base_layout_key as u32).to_string().into_bytes())?;
                 }
             }
         }
@@ -567,6 +948,14 @@ fn write_kitty_encoding(
     Ok(())
 }
 
+fn unshifted_char(c: char) -> char {
+    let mut lower = c.to_lowercase();
+    match (lower.next(), lower.next()) {
+        (Some(lower), None) => lower,
+        _ => c,
+    }
+}
+
 fn write_keycode_suffix(
     key_code: KeyCode,
     modifiers: KeyModifiers,
@@ -602,11 +991,7 @@ fn write_keycode_suffix(
         KeyCode::F(10) => buf.write_all(b"21~"),
         KeyCode::F(11) => buf.write_all(b"23~"),
         KeyCode::F(12) => buf.write_all(b"24~"),
-        KeyCode::Char(c) => {
-            let pos = buf.position() as usize;
-            let len = c.encode_utf8(&mut buf.get_mut()[pos..]).len();
-            buf.seek_relative(len as i64)
-        }
+        KeyCode::Char(c) => buf.write_all(c.encode_utf8(&mut [0; 4]).as_bytes()),
         KeyCode::Esc => buf.write_all(b"\x1B"),
         _ => return Ok(false),
     }?;
@@ -647,6 +1032,10 @@ fn convert_suffix_code(
     modifiers: KeyModifiers,
     buf: &mut Cursor<&mut [u8]>,
 ) -> io::Result<()> {
+    if let KeyCode::Char(c) = key_code {
+        // Multi-byte chars need to be written as a single codepoint
+        return buf.write_all(&(c as u32).to_string().into_bytes());
+    }
     let old_pos = buf.position() as usize;
     write_keycode_suffix(key_code, modifiers, false, buf)?;
     let new_pos = buf.position() as usize;
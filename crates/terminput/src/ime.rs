@@ -0,0 +1,33 @@
+use alloc::string::String;
+
+/// An input method editor (IME) event, used to compose text that can't be typed directly, such as
+/// CJK characters.
+#[derive(Debug, PartialOrd, PartialEq, Eq, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ImeEvent {
+    /// The IME was enabled. [`ImeEvent::Preedit`] and [`ImeEvent::Commit`] events may follow.
+    Enabled,
+    /// The text that's currently being composed. This replaces any previous preedit text. Empty
+    /// text means the preedit should be cleared.
+    Preedit {
+        /// The text being composed.
+        text: String,
+        /// The `(start, end)` byte offsets of the cursor or selection within the text, if the
+        /// backend reports it.
+        cursor: Option<(usize, usize)>,
+    },
+    /// The composed text that should be inserted.
+    Commit(String),
+    /// The IME was disabled.
+    Disabled,
+}
+
+impl ImeEvent {
+    /// Creates a new [`ImeEvent::Preedit`] without a cursor position.
+    pub fn preedit(text: impl Into<String>) -> Self {
+        Self::Preedit {
+            text: text.into(),
+            cursor: None,
+        }
+    }
+}
@@ -1,11 +1,17 @@
+use core::cmp::Ordering;
+use core::fmt;
 use core::hash::{Hash, Hasher};
 
 use bitflags::bitflags;
 
-use crate::Event;
+use crate::{Event, PhysicalKey};
 
 /// A key input event.
-#[derive(Debug, PartialOrd, Ord, Clone, Copy)]
+///
+/// Only [`KeyEvent::code`], [`KeyEvent::modifiers`], [`KeyEvent::kind`] and [`KeyEvent::state`]
+/// are used for equality, ordering and hashing, so events can be used as keys in a keymap
+/// regardless of the extra information reported by the input source.
+#[derive(Debug, Clone, Copy)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct KeyEvent {
     /// The key code.
@@ -16,6 +22,27 @@ pub struct KeyEvent {
     pub kind: KeyEventKind,
     /// Other keyboard properties.
     pub state: KeyEventState,
+    /// The text generated by the key press, if reported by the input source.
+    ///
+    /// This may differ from the text implied by [`KeyEvent::code`] when using non-US keyboard
+    /// layouts or input methods.
+    pub text: Option<KeyText>,
+    /// The character the key produces when shift is held, if reported by the input source.
+    pub shifted_key: Option<char>,
+    /// The character the key produces without shift, if reported by the input source and
+    /// different from [`KeyEvent::code`].
+    pub unshifted_key: Option<char>,
+    /// The character the key would produce on a standard US keyboard layout, if reported by the
+    /// input source and different from [`KeyEvent::code`].
+    ///
+    /// This is useful for keybindings that should work on other keyboard layouts, such as
+    /// matching Ctrl+C when the key is labeled with a Cyrillic character.
+    pub base_layout_key: Option<char>,
+    /// The physical location of the key, if reported by the input source.
+    ///
+    /// Unlike [`KeyEvent::code`], this doesn't depend on the keyboard layout, so it can be used for
+    /// keybindings that are based on the position of the key.
+    pub physical_key: Option<PhysicalKey>,
 }
 
 impl KeyEvent {
@@ -26,6 +53,11 @@ impl KeyEvent {
             modifiers: KeyModifiers::empty(),
             kind: KeyEventKind::Press,
             state: KeyEventState::empty(),
+            text: None,
+            shifted_key: None,
+            unshifted_key: None,
+            base_layout_key: None,
+            physical_key: None,
         }
     }
 
@@ -47,6 +79,36 @@ impl KeyEvent {
         self
     }
 
+    /// Sets the associated [`KeyText`].
+    pub const fn text(mut self, text: KeyText) -> Self {
+        self.text = Some(text);
+        self
+    }
+
+    /// Sets the shifted key.
+    pub const fn shifted_key(mut self, shifted_key: char) -> Self {
+        self.shifted_key = Some(shifted_key);
+        self
+    }
+
+    /// Sets the unshifted key.
+    pub const fn unshifted_key(mut self, unshifted_key: char) -> Self {
+        self.unshifted_key = Some(unshifted_key);
+        self
+    }
+
+    /// Sets the base layout key.
+    pub const fn base_layout_key(mut self, base_layout_key: char) -> Self {
+        self.base_layout_key = Some(base_layout_key);
+        self
+    }
+
+    /// Sets the [`PhysicalKey`].
+    pub const fn physical_key(mut self, physical_key: PhysicalKey) -> Self {
+        self.physical_key = Some(physical_key);
+        self
+    }
+
     /// Normalizes the event so the shift modifier is applied appropriately.
     pub fn normalize_case(mut self) -> Self {
         let c = match self.code {
@@ -70,12 +132,14 @@ impl PartialEq for KeyEvent {
             modifiers: lhs_modifiers,
             kind: lhs_kind,
             state: lhs_state,
+            ..
         } = self.normalize_case();
         let Self {
             code: rhs_code,
             modifiers: rhs_modifiers,
             kind: rhs_kind,
             state: rhs_state,
+            ..
         } = other.normalize_case();
         (lhs_code == rhs_code)
             && (lhs_modifiers == rhs_modifiers)
@@ -86,6 +150,37 @@ impl PartialEq for KeyEvent {
 
 impl Eq for KeyEvent {}
 
+impl PartialOrd for KeyEvent {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for KeyEvent {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let Self {
+            code: lhs_code,
+            modifiers: lhs_modifiers,
+            kind: lhs_kind,
+            state: lhs_state,
+            ..
+        } = self.normalize_case();
+        let Self {
+            code: rhs_code,
+            modifiers: rhs_modifiers,
+            kind: rhs_kind,
+            state: rhs_state,
+            ..
+        } = other.normalize_case();
+        (lhs_code, lhs_modifiers, lhs_kind, lhs_state).cmp(&(
+            rhs_code,
+            rhs_modifiers,
+            rhs_kind,
+            rhs_state,
+        ))
+    }
+}
+
 impl Hash for KeyEvent {
     fn hash<H: Hasher>(&self, hash_state: &mut H) {
         let Self {
@@ -93,6 +188,7 @@ impl Hash for KeyEvent {
             modifiers,
             kind,
             state,
+            ..
         } = self.normalize_case();
         code.hash(hash_state);
         modifiers.hash(hash_state);
@@ -101,6 +197,85 @@ impl Hash for KeyEvent {
     }
 }
 
+/// Text associated with a [`KeyEvent`].
+///
+/// The text is stored inline so that [`KeyEvent`] can remain [`Copy`]. It can hold up to
+/// [`KeyText::CAPACITY`] bytes of UTF-8, which is enough for any single character and most
+/// short sequences produced by input methods.
+#[derive(PartialOrd, Ord, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct KeyText {
+    bytes: [u8; Self::CAPACITY],
+    len: u8,
+}
+
+impl KeyText {
+    /// Maximum number of bytes that can be stored.
+    pub const CAPACITY: usize = 15;
+
+    /// Creates a new [`KeyText`]. Returns [`None`] if the text is longer than
+    /// [`KeyText::CAPACITY`] bytes.
+    pub fn new(text: &str) -> Option<Self> {
+        if text.len() > Self::CAPACITY {
+            return None;
+        }
+        let mut bytes = [0; Self::CAPACITY];
+        bytes[..text.len()].copy_from_slice(text.as_bytes());
+        Some(Self {
+            bytes,
+            len: text.len() as u8,
+        })
+    }
+
+    /// Returns the text as a string slice.
+    pub fn as_str(&self) -> &str {
+        core::str::from_utf8(&self.bytes[..self.len as usize]).unwrap_or_default()
+    }
+}
+
+impl From<char> for KeyText {
+    fn from(value: char) -> Self {
+        let mut bytes = [0; Self::CAPACITY];
+        let len = value.encode_utf8(&mut bytes).len();
+        Self {
+            bytes,
+            len: len as u8,
+        }
+    }
+}
+
+impl fmt::Debug for KeyText {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl fmt::Display for KeyText {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for KeyText {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for KeyText {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let text = alloc::string::String::deserialize(deserializer)?;
+        Self::new(&text).ok_or_else(|| serde::de::Error::custom("key text is too long"))
+    }
+}
+
 /// Represents whether the modifier came from the left or right side of the keyboard, where
 /// applicable.
 #[derive(Debug, PartialOrd, Ord, PartialEq, Eq, Clone, Copy, Hash)]
@@ -300,14 +475,40 @@ pub const CAPS_LOCK: KeyEventState = KeyEventState::CAPS_LOCK;
 /// Num lock event state.
 pub const NUM_LOCK: KeyEventState = KeyEventState::NUM_LOCK;
 
+bitflags! {
+    /// Controls which keyboard enhancement flags will be considered during encoding. These are
+    /// also reported by the terminal in
+    /// [`TerminalReply::KittyKeyboardFlags`](crate::TerminalReply::KittyKeyboardFlags).
+    /// These flags are described in Kitty's documentation on [progressive enhancement](https://sw.kovidgoyal.net/kitty/keyboard-protocol/#progressive-enhancement).
+    #[derive(Debug, PartialOrd, Ord, PartialEq, Eq, Clone, Copy, Hash)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(transparent))]
+    pub struct KittyFlags: u8 {
+        /// Represent escape and modified keys using CSI-u sequences.
+        const DISAMBIGUATE_ESCAPE_CODES = 1<<1;
+        /// Report release and repeat events.
+        const REPORT_EVENT_TYPES = 1<<2;
+        /// Send [alternate keycodes](https://sw.kovidgoyal.net/kitty/keyboard-protocol/#key-codes)
+        /// in addition to the base keycode. The encoder uses [`KeyEvent::shifted_key`],
+        /// [`KeyEvent::unshifted_key`] and [`KeyEvent::base_layout_key`] when they're set.
+        /// Otherwise, the shifted and unshifted keys can only be inferred for alphabetic keys since
+        /// applying this to other types of keys requires knowledge of the keyboard layout used to
+        /// type the key.
+        const REPORT_ALTERNATE_KEYS = 1<<3;
+        /// Represent all keyboard events as CSI-u sequences.
+        const REPORT_ALL_KEYS_AS_ESCAPE_CODES = 1<<4;
+        /// Send the [associated text](https://sw.kovidgoyal.net/kitty/keyboard-protocol/#text-as-code-points)
+        /// of the key event as a list of codepoints. This only takes effect when
+        /// [`REPORT_ALL_KEYS_AS_ESCAPE_CODES`](Self::REPORT_ALL_KEYS_AS_ESCAPE_CODES) is also set.
+        /// Text longer than [`KeyText::CAPACITY`] is parsed as [`Event::Text`] instead of a key
+        /// event.
+        const REPORT_ASSOCIATED_TEXT = 1<<5;
+
+    }
+}
+
 impl From<KeyCode> for KeyEvent {
     fn from(code: KeyCode) -> Self {
-        Self {
-            code,
-            modifiers: KeyModifiers::empty(),
-            kind: KeyEventKind::Press,
-            state: KeyEventState::empty(),
-        }
+        Self::new(code)
     }
 }
 
@@ -10,10 +10,14 @@ extern crate std;
 
 #[cfg(feature = "std")]
 mod encoder;
+mod ime;
 mod key;
 mod mouse;
 #[cfg(feature = "std")]
 mod parser;
+mod physical_key;
+mod reply;
+mod timed;
 
 use alloc::string::String;
 use core::error::Error;
@@ -21,8 +25,14 @@ use core::fmt;
 
 #[cfg(feature = "std")]
 pub use encoder::*;
+pub use ime::*;
 pub use key::*;
 pub use mouse::*;
+#[cfg(feature = "std")]
+pub use parser::*;
+pub use physical_key::*;
+pub use reply::*;
+pub use timed::*;
 
 /// The supplied event could not be converted into the requested type.
 #[derive(Debug)]
@@ -50,13 +60,26 @@ pub enum Event {
     Mouse(MouseEvent),
     /// A string that was pasted into the application.
     Paste(String),
+    /// Text that was entered without corresponding to a single key press, such as an emoji or a
+    /// grapheme made up of multiple codepoints. This is produced by GUI and web backends, which
+    /// deliver typed text separately from key events.
+    Text(String),
     /// An resize event with new dimensions after resize.
+    ///
+    /// When parsing, this is produced by in-band resize notifications (`CSI 48 ; rows ; cols ;
+    /// height ; width t`), which are sent when mode 2048 is enabled, and by the window size
+    /// report (`CSI 8 ; rows ; cols t`).
     Resize {
         /// New number of rows.
         rows: u32,
         /// New number of columns.
         cols: u32,
     },
+    /// A reply to a query sent to the terminal.
+    Reply(TerminalReply),
+    /// An input method editor (IME) event. Terminals handle composition themselves and only send
+    /// the committed text, so this is only produced by GUI and web backends.
+    Ime(ImeEvent),
 }
 
 /// Whether to include [`KeyEventKind::Repeat`] when checking for key down events.
@@ -303,6 +326,20 @@ impl Event {
         self.as_paste().is_some()
     }
 
+    /// Returns the text if the event is [`Event::Text`].
+    pub fn as_text(&self) -> Option<&str> {
+        if let Self::Text(text) = self {
+            Some(text)
+        } else {
+            None
+        }
+    }
+
+    /// Returns whether the event is [`Event::Text`].
+    pub fn is_text(&self) -> bool {
+        self.as_text().is_some()
+    }
+
     /// Returns the `(rows, cols)` from the contained event if it is a [`Event::Resize`] event.
     pub fn as_resize(&self) -> Option<(u32, u32)> {
         if let Self::Resize { rows, cols } = self {
@@ -316,4 +353,32 @@ impl Event {
     pub fn is_resize(&self) -> bool {
         self.as_resize().is_some()
     }
+
+    /// Returns the contained reply if this is a [`Event::Reply`].
+    pub fn as_reply(&self) -> Option<&TerminalReply> {
+        if let Self::Reply(reply) = self {
+            Some(reply)
+        } else {
+            None
+        }
+    }
+
+    /// Returns whether the event is [`Event::Reply`].
+    pub fn is_reply(&self) -> bool {
+        self.as_reply().is_some()
+    }
+
+    /// Returns the contained IME event if this is a [`Event::Ime`].
+    pub fn as_ime(&self) -> Option<&ImeEvent> {
+        if let Self::Ime(ime_event) = self {
+            Some(ime_event)
+        } else {
+            None
+        }
+    }
+
+    /// Returns whether the event is [`Event::Ime`].
+    pub fn is_ime(&self) -> bool {
+        self.as_ime().is_some()
+    }
 }
@@ -1,3 +1,6 @@
+use core::cmp::Ordering;
+use core::hash::{Hash, Hasher};
+
 use crate::{Event, KeyModifiers};
 
 /// A mouse event.
@@ -6,12 +9,174 @@ use crate::{Event, KeyModifiers};
 pub struct MouseEvent {
     /// The kind of mouse event that was caused.
     pub kind: MouseEventKind,
-    /// The column that the event occurred on.
+    /// The column that the event occurred on. If [`MouseEvent::unit`] is
+    /// [`PositionUnit::Pixels`], this is the horizontal position in pixels.
     pub column: u16,
-    /// The row that the event occurred on.
+    /// The row that the event occurred on. If [`MouseEvent::unit`] is [`PositionUnit::Pixels`],
+    /// this is the vertical position in pixels.
     pub row: u16,
     /// The key modifiers active when the event occurred.
     pub modifiers: KeyModifiers,
+    /// The unit of the event's position.
+    pub unit: PositionUnit,
+    /// The precise amount scrolled, for scroll events from backends that report it. Terminals
+    /// only report the [`ScrollDirection`].
+    pub scroll_amount: Option<ScrollAmount>,
+}
+
+impl MouseEvent {
+    /// Creates a new [`MouseEvent`] at the given cell position.
+    pub const fn new(kind: MouseEventKind, column: u16, row: u16) -> Self {
+        Self {
+            kind,
+            column,
+            row,
+            modifiers: KeyModifiers::NONE,
+            unit: PositionUnit::Cells,
+            scroll_amount: None,
+        }
+    }
+
+    /// Sets the [`KeyModifiers`].
+    pub const fn modifiers(mut self, modifiers: KeyModifiers) -> Self {
+        self.modifiers = modifiers;
+        self
+    }
+
+    /// Sets the [`PositionUnit`].
+    pub const fn unit(mut self, unit: PositionUnit) -> Self {
+        self.unit = unit;
+        self
+    }
+
+    /// Sets the [`ScrollAmount`].
+    pub const fn scroll_amount(mut self, scroll_amount: ScrollAmount) -> Self {
+        self.scroll_amount = Some(scroll_amount);
+        self
+    }
+}
+
+/// The unit used for the position of a [`MouseEvent`].
+#[derive(Debug, Default, PartialOrd, Ord, PartialEq, Eq, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PositionUnit {
+    /// The position is a zero-based cell coordinate.
+    #[default]
+    Cells,
+    /// The position is a zero-based pixel coordinate, as reported by terminals in SGR-Pixels mouse
+    /// mode (mode 1016).
+    Pixels,
+}
+
+/// Describes how a GUI or web terminal widget lays out its cells, in order to convert between
+/// pixel positions and cell positions.
+///
+/// Positions reported by the backend (CSS pixels for the DOM, points for egui) are logical
+/// pixels, while cell sizes are typically measured from the rendered font in physical pixels. The
+/// scale factor is the number of physical pixels per logical pixel, such as the
+/// `devicePixelRatio` of a browser window. It defaults to `1.0`.
+///
+/// # Example
+///
+/// ```
+/// use terminput::CellGeometry;
+///
+/// let geometry = CellGeometry::new(16.0, 32.0).origin(100.0, 50.0).scale_factor(2.0);
+/// assert_eq!(geometry.to_cell(120.0, 70.0), (2, 1));
+/// assert_eq!(geometry.to_cell(10.0, 10.0), (0, 0));
+/// assert_eq!(geometry.to_pixels(2, 1), (120.0, 74.0));
+/// ```
+#[derive(Debug, PartialEq, PartialOrd, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CellGeometry {
+    /// Horizontal position of the widget's top-left corner, in logical pixels.
+    pub origin_x: f32,
+    /// Vertical position of the widget's top-left corner, in logical pixels.
+    pub origin_y: f32,
+    /// Width of a single cell, in physical pixels.
+    pub cell_width: f32,
+    /// Height of a single cell, in physical pixels.
+    pub cell_height: f32,
+    /// Number of physical pixels per logical pixel.
+    pub scale_factor: f32,
+}
+
+impl Default for CellGeometry {
+    fn default() -> Self {
+        Self::new(1.0, 1.0)
+    }
+}
+
+impl CellGeometry {
+    /// Creates a new [`CellGeometry`] with the given cell size, positioned at the origin.
+    pub const fn new(cell_width: f32, cell_height: f32) -> Self {
+        Self {
+            origin_x: 0.0,
+            origin_y: 0.0,
+            cell_width,
+            cell_height,
+            scale_factor: 1.0,
+        }
+    }
+
+    /// Sets the position of the widget's top-left corner.
+    pub const fn origin(mut self, x: f32, y: f32) -> Self {
+        self.origin_x = x;
+        self.origin_y = y;
+        self
+    }
+
+    /// Sets the number of physical pixels per logical pixel.
+    pub const fn scale_factor(mut self, scale_factor: f32) -> Self {
+        self.scale_factor = scale_factor;
+        self
+    }
+
+    /// Converts a position in logical pixels to a `(column, row)` cell position. Positions above or
+    /// to the left of the widget are clamped to the first row or column.
+    pub fn to_cell(&self, x: f32, y: f32) -> (u16, u16) {
+        let (x, y) = self.to_physical(x, y);
+        // Float to int casts saturate, so negative positions become 0
+        ((x / self.cell_width) as u16, (y / self.cell_height) as u16)
+    }
+
+    /// Converts a position in logical pixels to a position in physical pixels relative to the
+    /// widget's top-left corner. This matches the coordinates used by terminals in SGR-Pixels mouse
+    /// mode.
+    pub fn to_physical(&self, x: f32, y: f32) -> (f32, f32) {
+        (
+            (x - self.origin_x) * self.scale_factor,
+            (y - self.origin_y) * self.scale_factor,
+        )
+    }
+
+    /// Converts a `(column, row)` cell position to the position of the cell's center in logical
+    /// pixels.
+    pub fn to_pixels(&self, column: u16, row: u16) -> (f32, f32) {
+        self.from_physical(
+            (f32::from(column) + 0.5) * self.cell_width,
+            (f32::from(row) + 0.5) * self.cell_height,
+        )
+    }
+
+    /// Converts a position in physical pixels relative to the widget's top-left corner to a
+    /// position in logical pixels.
+    pub fn from_physical(&self, x: f32, y: f32) -> (f32, f32) {
+        (
+            self.origin_x + x / self.scale_factor,
+            self.origin_y + y / self.scale_factor,
+        )
+    }
+
+    /// Converts the position of the [`MouseEvent`] to a position in logical pixels.
+    pub fn from_position(&self, mouse_event: &MouseEvent) -> (f32, f32) {
+        match mouse_event.unit {
+            PositionUnit::Cells => self.to_pixels(mouse_event.column, mouse_event.row),
+            PositionUnit::Pixels => {
+                self.from_physical(f32::from(mouse_event.column), f32::from(mouse_event.row))
+            }
+        }
+    }
 }
 
 /// Mouse scroll direction.
@@ -51,6 +216,73 @@ pub struct ScrollDelta {
     pub y: i32,
 }
 
+/// The precise amount scrolled by a scroll event.
+///
+/// The values use the same sign convention as [`ScrollDelta`], so scrolling up produces a positive
+/// `y` value and scrolling left produces a positive `x` value. Use [`ScrollDirection::delta`] as
+/// a fallback for events that don't have a precise amount.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ScrollAmount {
+    /// Amount scrolled in the x (horizontal) direction.
+    pub x: f32,
+    /// Amount scrolled in the y (vertical) direction.
+    pub y: f32,
+    /// The unit of the scroll amount.
+    pub unit: ScrollUnit,
+}
+
+impl ScrollAmount {
+    /// Creates a new [`ScrollAmount`].
+    pub const fn new(x: f32, y: f32, unit: ScrollUnit) -> Self {
+        Self { x, y, unit }
+    }
+}
+
+// Floats are compared by their total ordering so scroll events can still be used as map keys.
+impl PartialEq for ScrollAmount {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for ScrollAmount {}
+
+impl PartialOrd for ScrollAmount {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ScrollAmount {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.x
+            .total_cmp(&other.x)
+            .then_with(|| self.y.total_cmp(&other.y))
+            .then_with(|| self.unit.cmp(&other.unit))
+    }
+}
+
+impl Hash for ScrollAmount {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.x.to_bits().hash(state);
+        self.y.to_bits().hash(state);
+        self.unit.hash(state);
+    }
+}
+
+/// The unit of a [`ScrollAmount`].
+#[derive(Debug, PartialOrd, Ord, PartialEq, Eq, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ScrollUnit {
+    /// The amount is a number of lines.
+    Lines,
+    /// The amount is a number of pixels.
+    Pixels,
+    /// The amount is a number of pages.
+    Pages,
+}
+
 /// The type of mouse event.
 #[derive(Debug, PartialOrd, PartialEq, Eq, Clone, Copy, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -77,6 +309,19 @@ pub enum MouseButton {
     Right,
     /// Middle mouse button.
     Middle,
+    /// Back button, typically used to navigate backwards in history. This is button 8 in the X11
+    /// and xterm numbering.
+    Back,
+    /// Forward button, typically used to navigate forwards in history. This is button 9 in the X11
+    /// and xterm numbering.
+    Forward,
+    /// Any other mouse button, identified by its number in the X11 and xterm numbering, where
+    /// buttons 1 through 3 are left, middle, and right, and buttons 4 through 7 are used for
+    /// scrolling.
+    ///
+    /// Only buttons 8 through 11 can be encoded. Buttons 8 and 9 are encoded the same way as
+    /// [`MouseButton::Back`] and [`MouseButton::Forward`], so they're parsed as those variants.
+    Other(u8),
     /// Mouse button could not be determined.
     Unknown,
 }
@@ -0,0 +1,90 @@
+use std::error::Error;
+use std::vec::Vec;
+use std::{fmt, io};
+
+/// The reason a byte sequence could not be parsed into an event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ParseErrorKind {
+    /// The sequence contains bytes that are not valid UTF-8.
+    InvalidUtf8,
+    /// The sequence starts with an escape byte, but the bytes that follow don't form a known
+    /// sequence.
+    UnknownSequence,
+    /// The CSI sequence ends with a final byte that doesn't correspond to a known event.
+    UnknownCsiFinal(u8),
+    /// The SS3 sequence ends with a final byte that doesn't correspond to a known event.
+    UnknownSs3Final(u8),
+    /// The sequence's parameters are missing or could not be parsed.
+    MalformedParameters,
+    /// The sequence refers to a key code that isn't recognized.
+    UnknownKeyCode,
+    /// The mouse sequence refers to a button that isn't supported.
+    UnsupportedMouseButton,
+}
+
+impl fmt::Display for ParseErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidUtf8 => write!(f, "invalid UTF-8"),
+            Self::UnknownSequence => write!(f, "unknown escape sequence"),
+            Self::UnknownCsiFinal(byte) => write!(f, "unknown CSI final byte {byte:#04x}"),
+            Self::UnknownSs3Final(byte) => write!(f, "unknown SS3 final byte {byte:#04x}"),
+            Self::MalformedParameters => write!(f, "malformed parameters"),
+            Self::UnknownKeyCode => write!(f, "unknown key code"),
+            Self::UnsupportedMouseButton => write!(f, "unsupported mouse button"),
+        }
+    }
+}
+
+/// An error that occurred while parsing a byte sequence into an event.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ParseError {
+    kind: ParseErrorKind,
+    bytes: Vec<u8>,
+    offset: usize,
+}
+
+impl ParseError {
+    pub(crate) fn new(kind: ParseErrorKind, bytes: &[u8], offset: usize) -> Self {
+        Self {
+            kind,
+            bytes: bytes.to_vec(),
+            offset,
+        }
+    }
+
+    /// Returns the reason the sequence could not be parsed.
+    pub fn kind(&self) -> ParseErrorKind {
+        self.kind
+    }
+
+    /// Returns the bytes of the sequence that could not be parsed.
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Returns the offset within [`ParseError::bytes`] where the problem was detected.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Could not parse event: {} at offset {} in \"{}\"",
+            self.kind,
+            self.offset,
+            self.bytes.escape_ascii()
+        )
+    }
+}
+
+impl Error for ParseError {}
+
+impl From<ParseError> for io::Error {
+    fn from(value: ParseError) -> Self {
+        Self::new(io::ErrorKind::InvalidData, value)
+    }
+}
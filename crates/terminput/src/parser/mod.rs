@@ -1,16 +1,34 @@
 // This is a lightly modified version of crossterm's ansi escape sequence parser:
 // https://github.com/crossterm-rs/crossterm/blob/master/src/event/sys/unix/parse.rs
 
-use std::io;
+mod error;
+mod options;
+mod stream;
+
 use std::string::{String, ToString};
+use std::vec::Vec;
+
+pub use error::*;
+pub use options::*;
+pub use stream::*;
 
 use crate::{
-    Event, KeyCode, KeyEvent, KeyEventKind, KeyEventState, KeyModifiers, MediaKeyCode,
-    ModifierDirection, ModifierKeyCode, MouseButton, MouseEvent, MouseEventKind, ScrollDirection,
+    ClipboardSelection, ColorTarget, Event, KeyCode, KeyEvent, KeyEventKind, KeyEventState,
+    KeyModifiers, KeyText, KittyFlags, MediaKeyCode, ModeState, ModifierDirection, ModifierKeyCode,
+    MouseButton, MouseEvent, MouseEventKind, PhysicalKey, PositionUnit, RgbColor, ScrollDirection,
+    TerminalReply,
 };
 
-fn could_not_parse_event_error() -> io::Error {
-    io::Error::other("Could not parse event.")
+fn malformed_parameters_error(buffer: &[u8]) -> ParseError {
+    ParseError::new(ParseErrorKind::MalformedParameters, buffer, 2)
+}
+
+fn invalid_utf8_error(buffer: &[u8], offset: usize) -> ParseError {
+    ParseError::new(ParseErrorKind::InvalidUtf8, buffer, offset)
+}
+
+fn unknown_key_code_error(buffer: &[u8]) -> ParseError {
+    ParseError::new(ParseErrorKind::UnknownKeyCode, buffer, 2)
 }
 
 impl Event {
@@ -20,98 +38,203 @@ impl Event {
     ///
     /// Returns [`None`] if the input could be a valid event, but is incomplete.
     ///
-    /// Returns an [`io::Error`] if the input cannot be parsed into a valid event.
-    pub fn parse_from(buffer: &[u8]) -> io::Result<Option<Self>> {
-        if buffer.is_empty() {
-            return Ok(None);
-        }
+    /// Returns a [`ParseError`] if the input cannot be parsed into a valid event.
+    pub fn parse_from(buffer: &[u8]) -> Result<Option<Self>, ParseError> {
+        parse_event(buffer, false, ParserOptions::new())
+    }
+
+    /// Attempts to parse a byte sequence into an input event using the supplied
+    /// [`ParserOptions`].
+    ///
+    /// See [`Event::parse_from`] for details.
+    pub fn parse_with_options(
+        buffer: &[u8],
+        options: ParserOptions,
+    ) -> Result<Option<Self>, ParseError> {
+        parse_event(buffer, false, options)
+    }
+}
+
+/// Returns the key sent by the numeric keypad in application mode (DECKPAM) as `SS3 final`.
+fn keypad_key_code(final_byte: u8) -> Option<KeyCode> {
+    Some(match final_byte {
+        b'p'..=b'y' => KeyCode::Char((b'0' + final_byte - b'p') as char),
+        b'M' => KeyCode::Enter,
+        b'I' => KeyCode::Tab,
+        b' ' => KeyCode::Char(' '),
+        b'X' => KeyCode::Char('='),
+        b'j' => KeyCode::Char('*'),
+        b'k' => KeyCode::Char('+'),
+        b'l' => KeyCode::Char(','),
+        b'm' => KeyCode::Char('-'),
+        b'n' => KeyCode::Char('.'),
+        b'o' => KeyCode::Char('/'),
+        _ => return None,
+    })
+}
+
+/// Maps the lowercase final bytes that rxvt uses for modified arrow keys.
+fn rxvt_arrow_key_code(final_byte: u8) -> KeyCode {
+    match final_byte {
+        b'a' => KeyCode::Up,
+        b'b' => KeyCode::Down,
+        b'c' => KeyCode::Right,
+        _ => KeyCode::Left,
+    }
+}
+
+fn add_alt(event: Event) -> Event {
+    if let Event::Key(mut key_event) = event {
+        key_event.modifiers |= KeyModifiers::ALT;
+        Event::Key(key_event)
+    } else {
+        event
+    }
+}
+
+/// Parses a single event from the start of the buffer.
+///
+/// `input_available` signals that more bytes are known to follow the buffer, in which case a lone
+/// escape byte is treated as the start of a sequence instead of the Esc key.
+pub(crate) fn parse_event(
+    buffer: &[u8],
+    input_available: bool,
+    options: ParserOptions,
+) -> Result<Option<Event>, ParseError> {
+    if buffer.is_empty() {
+        return Ok(None);
+    }
 
-        match buffer[0] {
-            b'\x1B' => {
-                if buffer.len() == 1 {
-                    Ok(Some(Self::Key(KeyCode::Esc.into())))
+    match buffer[0] {
+        b'\x1B' => {
+            if buffer.len() == 1 {
+                if input_available {
+                    // Possible escape sequence
+                    Ok(None)
                 } else {
-                    match buffer[1] {
-                        b'O' => {
-                            if buffer.len() == 2 {
-                                Ok(None)
-                            } else {
-                                match buffer[2] {
-                                    b'D' => Ok(Some(Self::Key(KeyCode::Left.into()))),
-                                    b'C' => Ok(Some(Self::Key(KeyCode::Right.into()))),
-                                    b'A' => Ok(Some(Self::Key(KeyCode::Up.into()))),
-                                    b'B' => Ok(Some(Self::Key(KeyCode::Down.into()))),
-                                    b'H' => Ok(Some(Self::Key(KeyCode::Home.into()))),
-                                    b'F' => Ok(Some(Self::Key(KeyCode::End.into()))),
-                                    // F1-F4
-                                    val @ b'P'..=b'S' => {
-                                        Ok(Some(Self::Key(KeyCode::F(1 + val - b'P').into())))
-                                    }
-                                    _ => Err(could_not_parse_event_error()),
+                    Ok(Some(Event::Key(KeyCode::Esc.into())))
+                }
+            } else {
+                match buffer[1] {
+                    b'O' => {
+                        if buffer.len() == 2 {
+                            Ok(None)
+                        } else {
+                            match buffer[2] {
+                                b'D' => Ok(Some(Event::Key(KeyCode::Left.into()))),
+                                b'C' => Ok(Some(Event::Key(KeyCode::Right.into()))),
+                                b'A' => Ok(Some(Event::Key(KeyCode::Up.into()))),
+                                b'B' => Ok(Some(Event::Key(KeyCode::Down.into()))),
+                                b'H' => Ok(Some(Event::Key(KeyCode::Home.into()))),
+                                b'F' => Ok(Some(Event::Key(KeyCode::End.into()))),
+                                // rxvt Ctrl + arrow keys
+                                val @ b'a'..=b'd' => Ok(Some(Event::Key(
+                                    KeyEvent::new(rxvt_arrow_key_code(val))
+                                        .modifiers(KeyModifiers::CTRL),
+                                ))),
+                                // F1-F4
+                                val @ b'P'..=b'S' => {
+                                    Ok(Some(Event::Key(KeyCode::F(1 + val - b'P').into())))
                                 }
+                                val => match keypad_key_code(val) {
+                                    Some(code) => Ok(Some(Event::Key(
+                                        KeyEvent::new(code).state(KeyEventState::KEYPAD),
+                                    ))),
+                                    None => Err(ParseError::new(
+                                        ParseErrorKind::UnknownSs3Final(val),
+                                        buffer,
+                                        2,
+                                    )),
+                                },
                             }
                         }
-                        b'[' => parse_csi(buffer),
-                        b'\x1B' => {
-                            if buffer.len() == 2 {
-                                Ok(Some(Self::Key(
+                    }
+                    b'[' => parse_csi(buffer, options),
+                    b']' => parse_osc(buffer, input_available, options),
+                    b'P' => parse_dcs(buffer, input_available, options),
+                    b'\x1B' => {
+                        if buffer.len() == 2 {
+                            if input_available {
+                                // Possible alt + escape sequence
+                                Ok(None)
+                            } else {
+                                Ok(Some(Event::Key(
                                     KeyEvent::new(KeyCode::Esc).modifiers(KeyModifiers::ALT),
                                 )))
-                            } else {
-                                match &buffer[2..] {
-                                    b"[Z" => Ok(Some(Self::Key(
-                                        KeyEvent::new(KeyCode::Tab)
-                                            .modifiers(KeyModifiers::SHIFT | KeyModifiers::ALT),
-                                    ))),
-                                    _ => Err(could_not_parse_event_error()),
+                            }
+                        } else {
+                            match buffer[2] {
+                                // Alt + escape sequence, which is how rxvt and the Linux console
+                                // send Alt with special keys
+                                b'[' | b'O' => parse_event(&buffer[1..], input_available, options)
+                                    .map(|event_option| event_option.map(add_alt))
+                                    .map_err(|e| ParseError::new(e.kind(), buffer, e.offset() + 1)),
+                                _ => {
+                                    Err(ParseError::new(ParseErrorKind::UnknownSequence, buffer, 2))
                                 }
                             }
                         }
-                        _ => Self::parse_from(&buffer[1..]).map(|event_option| {
-                            event_option.map(|event| {
-                                if let Self::Key(key_event) = event {
-                                    let mut alt_key_event = key_event;
-                                    alt_key_event.modifiers |= KeyModifiers::ALT;
-                                    Self::Key(alt_key_event)
-                                } else {
-                                    event
-                                }
-                            })
-                        }),
                     }
+                    _ => parse_event(&buffer[1..], input_available, options)
+                        .map(|event_option| event_option.map(add_alt)),
                 }
             }
-            b'\r' => Ok(Some(Self::Key(KeyCode::Enter.into()))),
-            // Issue #371: \n = 0xA, which is also the keycode for Ctrl+J. The only reason we get
-            // newlines as input is because the terminal converts \r into \n for us. When we
-            // enter raw mode, we disable that, so \n no longer has any meaning - it's better to
-            // use Ctrl+J. Waiting to handle it here means it gets picked up later
-            // b'\n' if !crate::terminal::sys::is_raw_mode_enabled() => {
-            //     Ok(Some(Event::Key(KeyCode::Enter.into())))
-            // }
-            b'\t' => Ok(Some(Self::Key(KeyCode::Tab.into()))),
-            b'\x7F' => Ok(Some(Self::Key(KeyCode::Backspace.into()))),
-            c @ b'\x01'..=b'\x1A' => Ok(Some(Self::Key(
-                KeyEvent::new(KeyCode::Char((c - 0x1 + b'a') as char))
-                    .modifiers(KeyModifiers::CTRL),
-            ))),
-            c @ b'\x1C'..=b'\x1F' => Ok(Some(Self::Key(
-                KeyEvent::new(KeyCode::Char((c - 0x1C + b'4') as char))
-                    .modifiers(KeyModifiers::CTRL),
-            ))),
-            b'\0' => Ok(Some(Self::Key(
-                KeyEvent::new(KeyCode::Char(' ')).modifiers(KeyModifiers::CTRL),
-            ))),
-            _ => parse_utf8_char(buffer).map(|maybe_char| {
-                maybe_char
-                    .map(KeyCode::Char)
-                    .map(char_code_to_event)
-                    .map(Event::Key)
-            }),
         }
+        b'\r' => Ok(Some(Event::Key(KeyCode::Enter.into()))),
+        // Issue #371: \n = 0xA, which is also the keycode for Ctrl+J. The only reason we get
+        // newlines as input is because the terminal converts \r into \n for us. In raw mode, that
+        // conversion is disabled, so \n no longer has any meaning - it's better to use Ctrl+J
+        // unless the caller knows the conversion is enabled.
+        b'\n' if options.newline_as_enter => Ok(Some(Event::Key(KeyCode::Enter.into()))),
+        b'\t' if !options.tab_as_ctrl_i => Ok(Some(Event::Key(KeyCode::Tab.into()))),
+        b'\x08' if options.ctrl_h_as_backspace => Ok(Some(Event::Key(KeyCode::Backspace.into()))),
+        b'\x7F' => Ok(Some(Event::Key(KeyCode::Backspace.into()))),
+        c @ b'\x01'..=b'\x1A' => Ok(Some(Event::Key(
+            KeyEvent::new(KeyCode::Char((c - 0x1 + b'a') as char)).modifiers(KeyModifiers::CTRL),
+        ))),
+        c @ b'\x1C'..=b'\x1F' => {
+            let c = if options.ascii_control_punctuation {
+                ['\\', ']', '^', '_'][usize::from(c - 0x1C)]
+            } else {
+                (c - 0x1C + b'4') as char
+            };
+            Ok(Some(Event::Key(
+                KeyEvent::new(KeyCode::Char(c)).modifiers(KeyModifiers::CTRL),
+            )))
+        }
+        b'\0' => Ok(Some(Event::Key(
+            KeyEvent::new(KeyCode::Char(' ')).modifiers(KeyModifiers::CTRL),
+        ))),
+        b'\x8F' | b'\x9B' if options.eight_bit_controls => {
+            parse_eight_bit_control(buffer, input_available, options)
+        }
+        c @ 0x80..=0xFF if options.eight_bit_meta => {
+            // The high bit is the meta flag, so the rest of the byte is parsed on its own
+            parse_event(&[c & 0x7F], false, options).map(|event_option| event_option.map(add_alt))
+        }
+        _ => parse_utf8_char(buffer).map(|maybe_char| {
+            maybe_char
+                .map(KeyCode::Char)
+                .map(char_code_to_event)
+                .map(Event::Key)
+        }),
     }
 }
 
+fn parse_eight_bit_control(
+    buffer: &[u8],
+    input_available: bool,
+    options: ParserOptions,
+) -> Result<Option<Event>, ParseError> {
+    // The 8-bit C1 controls SS3 (0x8F) and CSI (0x9B) are equivalent to their 7-bit forms, ESC O
+    // and ESC [
+    let mut translated = Vec::with_capacity(buffer.len() + 1);
+    translated.extend_from_slice(&[b'\x1B', buffer[0] - 0x40]);
+    translated.extend_from_slice(&buffer[1..]);
+    parse_event(&translated, input_available, options)
+        .map_err(|e| ParseError::new(e.kind(), buffer, e.offset().saturating_sub(1)))
+}
+
 // converts KeyCode to KeyEvent (adds shift modifier in case of uppercase characters)
 fn char_code_to_event(code: KeyCode) -> KeyEvent {
     let modifiers = match code {
@@ -121,7 +244,10 @@ fn char_code_to_event(code: KeyCode) -> KeyEvent {
     KeyEvent::new(code).modifiers(modifiers)
 }
 
-pub(crate) fn parse_csi(buffer: &[u8]) -> io::Result<Option<Event>> {
+pub(crate) fn parse_csi(
+    buffer: &[u8],
+    options: ParserOptions,
+) -> Result<Option<Event>, ParseError> {
     assert!(buffer.starts_with(b"\x1B[")); // ESC [
 
     if buffer.len() == 2 {
@@ -137,7 +263,13 @@ pub(crate) fn parse_csi(buffer: &[u8]) -> io::Result<Option<Event>> {
                     // NOTE (@imdaveho): cannot find when this occurs;
                     // having another '[' after ESC[ not a likely scenario
                     val @ b'A'..=b'E' => Some(Event::Key(KeyCode::F(1 + val - b'A').into())),
-                    _ => return Err(could_not_parse_event_error()),
+                    val => {
+                        return Err(ParseError::new(
+                            ParseErrorKind::UnknownCsiFinal(val),
+                            buffer,
+                            3,
+                        ));
+                    }
                 }
             }
         }
@@ -147,11 +279,15 @@ pub(crate) fn parse_csi(buffer: &[u8]) -> io::Result<Option<Event>> {
         b'B' => Some(Event::Key(KeyCode::Down.into())),
         b'H' => Some(Event::Key(KeyCode::Home.into())),
         b'F' => Some(Event::Key(KeyCode::End.into())),
+        // rxvt Shift + arrow keys
+        val @ b'a'..=b'd' => Some(Event::Key(
+            KeyEvent::new(rxvt_arrow_key_code(val)).modifiers(KeyModifiers::SHIFT),
+        )),
         b'Z' => Some(Event::Key(
             KeyEvent::new(KeyCode::Tab).modifiers(KeyModifiers::SHIFT),
         )),
-        b'M' => return parse_csi_normal_mouse(buffer),
-        b'<' => return parse_csi_sgr_mouse(buffer),
+        b'M' => return parse_csi_normal_mouse(buffer, options),
+        b'<' => return parse_csi_sgr_mouse(buffer, options),
         b'I' => Some(Event::FocusGained),
         b'O' => Some(Event::FocusLost),
         b';' => return parse_csi_modifier_key_code(buffer),
@@ -164,10 +300,16 @@ pub(crate) fn parse_csi(buffer: &[u8]) -> io::Result<Option<Event>> {
         b'R' => Some(Event::Key(KeyCode::F(3).into())),
         b'S' => Some(Event::Key(KeyCode::F(4).into())),
         b'?' => match buffer[buffer.len() - 1] {
-            // Keyboard enhancement flags, not a valid input event
-            b'u' => return Err(could_not_parse_event_error()),
-            // Primary device attributes, not a valid input event
-            b'c' => return Err(could_not_parse_event_error()),
+            b'u' => return parse_csi_keyboard_enhancement_flags(buffer),
+            b'c' => return parse_csi_primary_device_attributes(buffer),
+            b'y' => return parse_csi_mode_report(buffer),
+            val @ 64..=126 => {
+                return Err(ParseError::new(
+                    ParseErrorKind::UnknownCsiFinal(val),
+                    buffer,
+                    buffer.len() - 1,
+                ));
+            }
             _ => None,
         },
         b'0'..=b'9' => {
@@ -178,6 +320,11 @@ pub(crate) fn parse_csi(buffer: &[u8]) -> io::Result<Option<Event>> {
                 // The final byte of a CSI sequence can be in the range 64-126, so
                 // let's keep reading anything else.
                 let last_byte = buffer[buffer.len() - 1];
+                if matches!(last_byte, b'$' | b'^' | b'@')
+                    && buffer[2..buffer.len() - 1].iter().all(u8::is_ascii_digit)
+                {
+                    return parse_csi_rxvt_special_key_code(buffer);
+                }
                 if !(64..=126).contains(&last_byte) {
                     None
                 } else {
@@ -186,41 +333,187 @@ pub(crate) fn parse_csi(buffer: &[u8]) -> io::Result<Option<Event>> {
                     }
                     match last_byte {
                         b'M' => return parse_csi_rxvt_mouse(buffer),
+                        b'~' if buffer.starts_with(b"\x1B[27;") => {
+                            return parse_csi_modify_other_keys(buffer);
+                        }
                         b'~' => return parse_csi_special_key_code(buffer),
                         b'u' => return parse_csi_u_encoded_key_code(buffer),
+                        b'y' => return parse_csi_mode_report(buffer),
+                        b't' => return parse_csi_window_report(buffer),
+                        b'R' if !is_modified_f3(buffer, options) => {
+                            return parse_csi_cursor_position(buffer);
+                        }
                         _ => return parse_csi_modifier_key_code(buffer),
                     }
                 }
             }
         }
-        _ => return Err(could_not_parse_event_error()),
+        val => {
+            return Err(ParseError::new(
+                ParseErrorKind::UnknownCsiFinal(val),
+                buffer,
+                2,
+            ));
+        }
     };
 
     Ok(input_event)
 }
 
-pub(crate) fn next_parsed<T>(iter: &mut dyn Iterator<Item = &str>) -> io::Result<T>
+fn is_modified_f3(buffer: &[u8], options: ParserOptions) -> bool {
+    // xterm sends F3 with modifiers as `CSI 1 ; modifiers R`, which is also a valid cursor
+    // position report. We assume it's a key event if an event type is present, or if the
+    // modifiers are in the range xterm uses and the caller isn't expecting a report.
+    let Some(params) = buffer[2..buffer.len() - 1].strip_prefix(b"1;") else {
+        return false;
+    };
+    params.contains(&b':')
+        || !options.cursor_position_reports
+            && matches!(
+                std::str::from_utf8(params)
+                    .ok()
+                    .and_then(|s| s.parse::<u8>().ok()),
+                Some(2..=16)
+            )
+}
+
+pub(crate) fn parse_csi_cursor_position(buffer: &[u8]) -> Result<Option<Event>, ParseError> {
+    // ESC [ Cy ; Cx R
+    //   Cy - cursor row number (starting from 1)
+    //   Cx - cursor column number (starting from 1)
+    assert!(buffer.starts_with(b"\x1B[")); // ESC [
+    assert!(buffer.ends_with(b"R"));
+
+    let s = std::str::from_utf8(&buffer[2..buffer.len() - 1])
+        .map_err(|e| invalid_utf8_error(buffer, 2 + e.valid_up_to()))?;
+    let mut split = s.split(';');
+
+    let row = next_parsed::<u16>(&mut split, buffer)?.saturating_sub(1);
+    let column = next_parsed::<u16>(&mut split, buffer)?.saturating_sub(1);
+
+    Ok(Some(Event::Reply(TerminalReply::CursorPosition {
+        row,
+        column,
+    })))
+}
+
+fn parse_csi_keyboard_enhancement_flags(buffer: &[u8]) -> Result<Option<Event>, ParseError> {
+    // ESC [ ? flags u
+    assert!(buffer.starts_with(b"\x1B[?")); // ESC [ ?
+    assert!(buffer.ends_with(b"u"));
+
+    let s = std::str::from_utf8(&buffer[3..buffer.len() - 1])
+        .map_err(|e| invalid_utf8_error(buffer, 3 + e.valid_up_to()))?;
+    let bits = s
+        .parse::<u8>()
+        .map_err(|_| malformed_parameters_error(buffer))?;
+    // The protocol's flag values start at 1 rather than 2
+    let flags = KittyFlags::from_bits_truncate(bits << 1);
+
+    Ok(Some(Event::Reply(TerminalReply::KittyKeyboardFlags(flags))))
+}
+
+fn parse_csi_primary_device_attributes(buffer: &[u8]) -> Result<Option<Event>, ParseError> {
+    // ESC [ ? 64 ; attr1 ; attr2 ; ... ; attrn ; c
+    assert!(buffer.starts_with(b"\x1B[?")); // ESC [ ?
+    assert!(buffer.ends_with(b"c"));
+
+    let s = std::str::from_utf8(&buffer[3..buffer.len() - 1])
+        .map_err(|e| invalid_utf8_error(buffer, 3 + e.valid_up_to()))?;
+    let attributes = s
+        .split(';')
+        .filter(|attribute| !attribute.is_empty())
+        .map(|attribute| attribute.parse::<u16>())
+        .collect::<Result<_, _>>()
+        .map_err(|_| malformed_parameters_error(buffer))?;
+
+    Ok(Some(Event::Reply(TerminalReply::PrimaryDeviceAttributes(
+        attributes,
+    ))))
+}
+
+fn parse_csi_window_report(buffer: &[u8]) -> Result<Option<Event>, ParseError> {
+    // ESC [ 48 ; rows ; cols ; height ; width t (in-band resize notification)
+    // ESC [ 8 ; rows ; cols t (text area size report)
+    //   height - text area height in pixels
+    //   width - text area width in pixels
+    assert!(buffer.starts_with(b"\x1B[")); // ESC [
+    assert!(buffer.ends_with(b"t"));
+
+    let s = std::str::from_utf8(&buffer[2..buffer.len() - 1])
+        .map_err(|e| invalid_utf8_error(buffer, 2 + e.valid_up_to()))?;
+    let mut split = s.split(';');
+
+    match next_parsed::<u8>(&mut split, buffer)? {
+        8 | 48 => {}
+        _ => return Err(ParseError::new(ParseErrorKind::UnknownSequence, buffer, 2)),
+    }
+    let rows = next_parsed::<u32>(&mut split, buffer)?;
+    let cols = next_parsed::<u32>(&mut split, buffer)?;
+
+    Ok(Some(Event::Resize { rows, cols }))
+}
+
+fn parse_csi_mode_report(buffer: &[u8]) -> Result<Option<Event>, ParseError> {
+    // ESC [ ? Pm ; Ps $ y (DEC private mode)
+    // ESC [ Pm ; Ps $ y (ANSI mode)
+    //   Pm - mode number
+    //   Ps - mode state
+    assert!(buffer.starts_with(b"\x1B[")); // ESC [
+    assert!(buffer.ends_with(b"y"));
+
+    if !buffer.ends_with(b"$y") {
+        return Err(ParseError::new(
+            ParseErrorKind::UnknownCsiFinal(b'y'),
+            buffer,
+            buffer.len() - 1,
+        ));
+    }
+
+    let private = buffer[2] == b'?';
+    let start = if private { 3 } else { 2 };
+    let s = std::str::from_utf8(&buffer[start..buffer.len() - 2])
+        .map_err(|e| invalid_utf8_error(buffer, start + e.valid_up_to()))?;
+    let mut split = s.split(';');
+
+    let mode = next_parsed::<u16>(&mut split, buffer)?;
+    let state = match next_parsed::<u8>(&mut split, buffer)? {
+        0 => ModeState::NotRecognized,
+        1 => ModeState::Set,
+        2 => ModeState::Reset,
+        3 => ModeState::PermanentlySet,
+        4 => ModeState::PermanentlyReset,
+        _ => return Err(malformed_parameters_error(buffer)),
+    };
+
+    Ok(Some(Event::Reply(TerminalReply::ModeReport {
+        mode,
+        private,
+        state,
+    })))
+}
+
+pub(crate) fn next_parsed<T>(
+    iter: &mut dyn Iterator<Item = &str>,
+    buffer: &[u8],
+) -> Result<T, ParseError>
 where
     T: std::str::FromStr,
 {
     iter.next()
-        .ok_or_else(could_not_parse_event_error)?
-        .parse::<T>()
-        .map_err(|_| could_not_parse_event_error())
+        .and_then(|s| s.parse::<T>().ok())
+        .ok_or_else(|| malformed_parameters_error(buffer))
 }
 
-fn modifier_and_kind_parsed(iter: &mut dyn Iterator<Item = &str>) -> io::Result<(u8, u8)> {
-    let mut sub_split = iter
-        .next()
-        .ok_or_else(could_not_parse_event_error)?
-        .split(':');
+fn modifier_and_kind_parsed(iter: &mut dyn Iterator<Item = &str>) -> Option<(u8, u8)> {
+    let mut sub_split = iter.next()?.split(':');
 
-    let modifier_mask = next_parsed::<u8>(&mut sub_split)?;
+    let modifier_mask = sub_split.next()?.parse::<u8>().ok()?;
 
-    if let Ok(kind_code) = next_parsed::<u8>(&mut sub_split) {
-        Ok((modifier_mask, kind_code))
+    if let Some(kind_code) = sub_split.next().and_then(|s| s.parse::<u8>().ok()) {
+        Some((modifier_mask, kind_code))
     } else {
-        Ok((modifier_mask, 1))
+        Some((modifier_mask, 1))
     }
 }
 
@@ -269,33 +562,34 @@ fn parse_key_event_kind(kind: u8) -> KeyEventKind {
     }
 }
 
-pub(crate) fn parse_csi_modifier_key_code(buffer: &[u8]) -> io::Result<Option<Event>> {
+pub(crate) fn parse_csi_modifier_key_code(buffer: &[u8]) -> Result<Option<Event>, ParseError> {
     assert!(buffer.starts_with(b"\x1B[")); // ESC [
 
     let s = std::str::from_utf8(&buffer[2..buffer.len() - 1])
-        .map_err(|_| could_not_parse_event_error())?;
+        .map_err(|e| invalid_utf8_error(buffer, 2 + e.valid_up_to()))?;
     let mut split = s.split(';');
 
     split.next();
 
-    let (modifiers, kind) =
-        if let Ok((modifier_mask, kind_code)) = modifier_and_kind_parsed(&mut split) {
-            (
-                parse_modifiers(modifier_mask),
-                parse_key_event_kind(kind_code),
-            )
-        } else if buffer.len() > 3 {
-            (
-                parse_modifiers(
-                    (buffer[buffer.len() - 2] as char)
-                        .to_digit(10)
-                        .ok_or_else(could_not_parse_event_error)? as u8,
-                ),
-                KeyEventKind::Press,
-            )
-        } else {
-            (KeyModifiers::NONE, KeyEventKind::Press)
-        };
+    let (modifiers, kind) = if let Some((modifier_mask, kind_code)) =
+        modifier_and_kind_parsed(&mut split)
+    {
+        (
+            parse_modifiers(modifier_mask),
+            parse_key_event_kind(kind_code),
+        )
+    } else if buffer.len() > 3 {
+        (
+            parse_modifiers(
+                (buffer[buffer.len() - 2] as char)
+                    .to_digit(10)
+                    .ok_or_else(|| malformed_parameters_error(buffer))? as u8,
+            ),
+            KeyEventKind::Press,
+        )
+    } else {
+        (KeyModifiers::NONE, KeyEventKind::Press)
+    };
     let key = buffer[buffer.len() - 1];
 
     let keycode = match key {
@@ -309,7 +603,13 @@ pub(crate) fn parse_csi_modifier_key_code(buffer: &[u8]) -> io::Result<Option<Ev
         b'Q' => KeyCode::F(2),
         b'R' => KeyCode::F(3),
         b'S' => KeyCode::F(4),
-        _ => return Err(could_not_parse_event_error()),
+        _ => {
+            return Err(ParseError::new(
+                ParseErrorKind::UnknownCsiFinal(key),
+                buffer,
+                buffer.len() - 1,
+            ));
+        }
     };
 
     let input_event = Event::Key(KeyEvent::new(keycode).modifiers(modifiers).kind(kind));
@@ -460,7 +760,7 @@ fn translate_functional_key_code(codepoint: u32) -> Option<(KeyCode, KeyEventSta
     None
 }
 
-pub(crate) fn parse_csi_u_encoded_key_code(buffer: &[u8]) -> io::Result<Option<Event>> {
+pub(crate) fn parse_csi_u_encoded_key_code(buffer: &[u8]) -> Result<Option<Event>, ParseError> {
     assert!(buffer.starts_with(b"\x1B[")); // ESC [
     assert!(buffer.ends_with(b"u"));
 
@@ -469,7 +769,7 @@ pub(crate) fn parse_csi_u_encoded_key_code(buffer: &[u8]) -> io::Result<Option<E
     // or Kitty Keyboard Protocol (https://sw.kovidgoyal.net/kitty/keyboard-protocol/) specifications.
     // This CSI sequence is a tuple of semicolon-separated numbers.
     let s = std::str::from_utf8(&buffer[2..buffer.len() - 1])
-        .map_err(|_| could_not_parse_event_error())?;
+        .map_err(|e| invalid_utf8_error(buffer, 2 + e.valid_up_to()))?;
     let mut split = s.split(';');
 
     // In `CSI u`, this is parsed as:
@@ -483,17 +783,13 @@ pub(crate) fn parse_csi_u_encoded_key_code(buffer: &[u8]) -> io::Result<Option<E
     //     CSI unicode-key-code:alternate-key-codes ; modifiers:event-type ; text-as-codepoints u
     let mut codepoints = split
         .next()
-        .ok_or_else(could_not_parse_event_error)?
+        .ok_or_else(|| malformed_parameters_error(buffer))?
         .split(':');
 
-    let codepoint = codepoints
-        .next()
-        .ok_or_else(could_not_parse_event_error)?
-        .parse::<u32>()
-        .map_err(|_| could_not_parse_event_error())?;
+    let codepoint = next_parsed::<u32>(&mut codepoints, buffer)?;
 
     let (mut modifiers, kind, state_from_modifiers) =
-        if let Ok((modifier_mask, kind_code)) = modifier_and_kind_parsed(&mut split) {
+        if let Some((modifier_mask, kind_code)) = modifier_and_kind_parsed(&mut split) {
             (
                 parse_modifiers(modifier_mask),
                 parse_key_event_kind(kind_code),
@@ -507,24 +803,9 @@ pub(crate) fn parse_csi_u_encoded_key_code(buffer: &[u8]) -> io::Result<Option<E
         if let Some((special_key_code, state)) = translate_functional_key_code(codepoint) {
             (special_key_code, state)
         } else if let Some(c) = char::from_u32(codepoint) {
-            (
-                match c {
-                    '\x1B' => KeyCode::Esc,
-                    '\r' => KeyCode::Enter,
-                    // Issue #371: \n = 0xA, which is also the keycode for Ctrl+J. The only reason
-                    // we get newlines as input is because the terminal converts
-                    // \r into \n for us. When we enter raw mode, we disable
-                    // that, so \n no longer has any meaning - it's better to
-                    // use Ctrl+J. Waiting to handle it here means it gets picked up later
-                    // '\n' if !crate::terminal::sys::is_raw_mode_enabled() => KeyCode::Enter,
-                    '\t' => KeyCode::Tab,
-                    '\x7F' => KeyCode::Backspace,
-                    _ => KeyCode::Char(c),
-                },
-                KeyEventState::empty(),
-            )
+            (char_to_key_code(c), KeyEventState::empty())
         } else {
-            return Err(could_not_parse_event_error());
+            return Err(unknown_key_code_error(buffer));
         }
     };
 
@@ -552,43 +833,116 @@ pub(crate) fn parse_csi_u_encoded_key_code(buffer: &[u8]) -> io::Result<Option<E
         }
     }
 
-    // When the "report alternate keys" flag is enabled in the Kitty Keyboard Protocol
-    // and the terminal sends a keyboard event containing shift, the sequence will
-    // contain an additional codepoint separated by a ':' character which contains
-    // the shifted character according to the keyboard layout.
+    // When the "report alternate keys" flag is enabled in the Kitty Keyboard Protocol, the
+    // sequence may contain additional codepoints separated by ':' characters. The first is the
+    // shifted character according to the keyboard layout, which is only sent when shift is held.
+    // The second is the key in the standard PC-101 layout, which may be sent with an empty
+    // shifted field.
+    let mut alternate_key =
+        || -> Option<char> { char::from_u32(codepoints.next()?.parse::<u32>().ok()?) };
+    let shifted_key = alternate_key();
+    let base_layout_key = alternate_key();
+
+    // The unshifted key is kept so the original sequence can be encoded again
+    let mut unshifted_key = None;
     if modifiers.contains(KeyModifiers::SHIFT)
-        && let Some(shifted_c) = codepoints
-            .next()
-            .and_then(|codepoint| codepoint.parse::<u32>().ok())
-            .and_then(char::from_u32)
+        && let Some(shifted_c) = shifted_key
     {
+        if let KeyCode::Char(c) = keycode
+            && c != shifted_c
+        {
+            unshifted_key = Some(c);
+        }
         keycode = KeyCode::Char(shifted_c);
         modifiers.set(KeyModifiers::SHIFT, false);
     }
 
+    let mut key_event = KeyEvent::new(keycode)
+        .modifiers(modifiers)
+        .kind(kind)
+        .state(state_from_keycode | state_from_modifiers);
+    key_event.shifted_key = shifted_key;
+    key_event.unshifted_key = unshifted_key;
+    key_event.base_layout_key = base_layout_key;
+    key_event.physical_key = base_layout_key.and_then(PhysicalKey::from_us_layout_char);
+
+    // When the "report associated text" flag is enabled, the text generated by the key press is
+    // sent as a ':'-separated list of codepoints in the third field.
+    if let Some(text) = split.next().filter(|text| !text.is_empty()) {
+        let text = text
+            .split(':')
+            .map(|codepoint| codepoint.parse::<u32>().ok().and_then(char::from_u32))
+            .collect::<Option<String>>()
+            .ok_or_else(|| malformed_parameters_error(buffer))?;
+        match KeyText::new(&text) {
+            Some(text) => key_event.text = Some(text),
+            // Text that's too long to store with the key is reported on its own so it isn't lost
+            None => return Ok(Some(Event::Text(text))),
+        }
+    }
+
+    Ok(Some(Event::Key(key_event)))
+}
+
+fn char_to_key_code(c: char) -> KeyCode {
+    match c {
+        '\x1B' => KeyCode::Esc,
+        '\r' => KeyCode::Enter,
+        // Issue #371: \n = 0xA, which is also the keycode for Ctrl+J. The only reason
+        // we get newlines as input is because the terminal converts
+        // \r into \n for us. When we enter raw mode, we disable
+        // that, so \n no longer has any meaning - it's better to
+        // use Ctrl+J. Waiting to handle it here means it gets picked up later
+        // '\n' if !crate::terminal::sys::is_raw_mode_enabled() => KeyCode::Enter,
+        '\t' => KeyCode::Tab,
+        // xterm sends Ctrl+H as the letter with modifiers, so a backspace character can only
+        // come from the Backspace key
+        '\x08' | '\x7F' => KeyCode::Backspace,
+        _ => KeyCode::Char(c),
+    }
+}
+
+pub(crate) fn parse_csi_modify_other_keys(buffer: &[u8]) -> Result<Option<Event>, ParseError> {
+    // xterm's modifyOtherKeys encoding:
+    // ESC [ 27 ; modifiers ; codepoint ~
+    //
+    // With formatOtherKeys=1, xterm sends `CSI codepoint ; modifiers u` instead, which is handled
+    // by the CSI u parser.
+    assert!(buffer.starts_with(b"\x1B[27;")); // ESC [ 2 7 ;
+    assert!(buffer.ends_with(b"~"));
+
+    let s = std::str::from_utf8(&buffer[5..buffer.len() - 1])
+        .map_err(|e| invalid_utf8_error(buffer, 5 + e.valid_up_to()))?;
+    let mut split = s.split(';');
+
+    let modifier_mask = next_parsed::<u8>(&mut split, buffer)?;
+    let codepoint = next_parsed::<u32>(&mut split, buffer)?;
+    let keycode = char::from_u32(codepoint)
+        .map(char_to_key_code)
+        .ok_or_else(|| unknown_key_code_error(buffer))?;
+
     let input_event = Event::Key(
         KeyEvent::new(keycode)
-            .modifiers(modifiers)
-            .kind(kind)
-            .state(state_from_keycode | state_from_modifiers),
+            .modifiers(parse_modifiers(modifier_mask))
+            .state(parse_modifiers_to_state(modifier_mask)),
     );
 
     Ok(Some(input_event))
 }
 
-pub(crate) fn parse_csi_special_key_code(buffer: &[u8]) -> io::Result<Option<Event>> {
+pub(crate) fn parse_csi_special_key_code(buffer: &[u8]) -> Result<Option<Event>, ParseError> {
     assert!(buffer.starts_with(b"\x1B[")); // ESC [
     assert!(buffer.ends_with(b"~"));
 
     let s = std::str::from_utf8(&buffer[2..buffer.len() - 1])
-        .map_err(|_| could_not_parse_event_error())?;
+        .map_err(|e| invalid_utf8_error(buffer, 2 + e.valid_up_to()))?;
     let mut split = s.split(';');
 
     // This CSI sequence can be a list of semicolon-separated numbers.
-    let first = next_parsed::<u8>(&mut split)?;
+    let first = next_parsed::<u8>(&mut split, buffer)?;
 
     let (modifiers, kind, state) =
-        if let Ok((modifier_mask, kind_code)) = modifier_and_kind_parsed(&mut split) {
+        if let Some((modifier_mask, kind_code)) = modifier_and_kind_parsed(&mut split) {
             (
                 parse_modifiers(modifier_mask),
                 parse_key_event_kind(kind_code),
@@ -598,7 +952,41 @@ pub(crate) fn parse_csi_special_key_code(buffer: &[u8]) -> io::Result<Option<Eve
             (KeyModifiers::NONE, KeyEventKind::Press, KeyEventState::NONE)
         };
 
-    let keycode = match first {
+    let input_event = Event::Key(
+        KeyEvent::new(special_key_code(first, buffer)?)
+            .modifiers(modifiers)
+            .kind(kind)
+            .state(state),
+    );
+
+    Ok(Some(input_event))
+}
+
+pub(crate) fn parse_csi_rxvt_special_key_code(buffer: &[u8]) -> Result<Option<Event>, ParseError> {
+    // rxvt sends modifiers as the final byte instead of as a parameter:
+    // ESC [ number ~ (none), ESC [ number $ (Shift), ESC [ number ^ (Ctrl) and
+    // ESC [ number @ (Ctrl + Shift)
+    assert!(buffer.starts_with(b"\x1B[")); // ESC [
+
+    let s = std::str::from_utf8(&buffer[2..buffer.len() - 1])
+        .map_err(|e| invalid_utf8_error(buffer, 2 + e.valid_up_to()))?;
+    let number = s
+        .parse::<u8>()
+        .map_err(|_| malformed_parameters_error(buffer))?;
+    let modifiers = match buffer[buffer.len() - 1] {
+        b'$' => KeyModifiers::SHIFT,
+        b'^' => KeyModifiers::CTRL,
+        _ => KeyModifiers::CTRL | KeyModifiers::SHIFT,
+    };
+
+    Ok(Some(Event::Key(
+        KeyEvent::new(special_key_code(number, buffer)?).modifiers(modifiers),
+    )))
+}
+
+fn special_key_code(number: u8, buffer: &[u8]) -> Result<KeyCode, ParseError> {
+    // 1 and 4 are sent by the Linux console, screen and tmux, 7 and 8 by rxvt
+    Ok(match number {
         1 | 7 => KeyCode::Home,
         2 => KeyCode::Insert,
         3 => KeyCode::Delete,
@@ -610,20 +998,11 @@ pub(crate) fn parse_csi_special_key_code(buffer: &[u8]) -> io::Result<Option<Eve
         v @ 23..=26 => KeyCode::F(v - 12),
         v @ 28..=29 => KeyCode::F(v - 15),
         v @ 31..=34 => KeyCode::F(v - 17),
-        _ => return Err(could_not_parse_event_error()),
-    };
-
-    let input_event = Event::Key(
-        KeyEvent::new(keycode)
-            .modifiers(modifiers)
-            .kind(kind)
-            .state(state),
-    );
-
-    Ok(Some(input_event))
+        _ => return Err(unknown_key_code_error(buffer)),
+    })
 }
 
-pub(crate) fn parse_csi_rxvt_mouse(buffer: &[u8]) -> io::Result<Option<Event>> {
+pub(crate) fn parse_csi_rxvt_mouse(buffer: &[u8]) -> Result<Option<Event>, ParseError> {
     // rxvt mouse encoding:
     // ESC [ Cb ; Cx ; Cy ; M
 
@@ -631,54 +1010,95 @@ pub(crate) fn parse_csi_rxvt_mouse(buffer: &[u8]) -> io::Result<Option<Event>> {
     assert!(buffer.ends_with(b"M"));
 
     let s = std::str::from_utf8(&buffer[2..buffer.len() - 1])
-        .map_err(|_| could_not_parse_event_error())?;
+        .map_err(|e| invalid_utf8_error(buffer, 2 + e.valid_up_to()))?;
     let mut split = s.split(';');
 
-    let cb = next_parsed::<u8>(&mut split)?
+    let cb = next_parsed::<u8>(&mut split, buffer)?
         .checked_sub(32)
-        .ok_or_else(could_not_parse_event_error)?;
-    let (kind, modifiers) = parse_cb(cb)?;
+        .ok_or_else(|| malformed_parameters_error(buffer))?;
+    let (kind, modifiers) = parse_cb(cb, buffer)?;
 
-    let cx = next_parsed::<u16>(&mut split)? - 1;
-    let cy = next_parsed::<u16>(&mut split)? - 1;
+    let cx = next_parsed::<u16>(&mut split, buffer)?.saturating_sub(1);
+    let cy = next_parsed::<u16>(&mut split, buffer)?.saturating_sub(1);
 
-    Ok(Some(Event::Mouse(MouseEvent {
-        kind,
-        column: cx,
-        row: cy,
-        modifiers,
-    })))
+    Ok(Some(Event::Mouse(
+        MouseEvent::new(kind, cx, cy).modifiers(modifiers),
+    )))
 }
 
-pub(crate) fn parse_csi_normal_mouse(buffer: &[u8]) -> io::Result<Option<Event>> {
+pub(crate) fn parse_csi_normal_mouse(
+    buffer: &[u8],
+    options: ParserOptions,
+) -> Result<Option<Event>, ParseError> {
     // Normal mouse encoding: ESC [ M CB Cx Cy (6 characters only).
+    // UTF-8 mouse encoding (mode 1005) uses the same format, but each value is a UTF-8 encoded
+    // character, so positions beyond 223 can be represented.
 
     assert!(buffer.starts_with(b"\x1B[M")); // ESC [ M
 
-    if buffer.len() < 6 {
-        return Ok(None);
-    }
+    let (cb, cx, cy) = if options.utf8_mouse {
+        let mut values = [0; 3];
+        let mut pos = 3;
+        for value in &mut values {
+            let Some((c, len)) = parse_utf8_mouse_value(buffer, pos)? else {
+                return Ok(None);
+            };
+            *value = c;
+            pos += len;
+        }
+        if pos != buffer.len() {
+            return Err(malformed_parameters_error(buffer));
+        }
+        let cb = u8::try_from(values[0])
+            .map_err(|_| ParseError::new(ParseErrorKind::MalformedParameters, buffer, 3))?;
+        (cb, values[1], values[2])
+    } else {
+        if buffer.len() < 6 {
+            return Ok(None);
+        }
+        (buffer[3], u32::from(buffer[4]), u32::from(buffer[5]))
+    };
 
-    let cb = buffer[3]
+    let cb = cb
         .checked_sub(32)
-        .ok_or_else(could_not_parse_event_error)?;
-    let (kind, modifiers) = parse_cb(cb)?;
+        .ok_or_else(|| ParseError::new(ParseErrorKind::MalformedParameters, buffer, 3))?;
+    let (kind, modifiers) = parse_cb(cb, buffer)?;
 
     // See http://www.xfree86.org/current/ctlseqs.html#Mouse%20Tracking
     // The upper left character position on the terminal is denoted as 1,1.
     // Subtract 1 to keep it synced with cursor
-    let cx = u16::from(buffer[4].saturating_sub(33));
-    let cy = u16::from(buffer[5].saturating_sub(33));
-
-    Ok(Some(Event::Mouse(MouseEvent {
-        kind,
-        column: cx,
-        row: cy,
-        modifiers,
-    })))
+    let cx = cx.saturating_sub(33) as u16;
+    let cy = cy.saturating_sub(33) as u16;
+
+    Ok(Some(Event::Mouse(
+        MouseEvent::new(kind, cx, cy).modifiers(modifiers),
+    )))
+}
+
+fn parse_utf8_mouse_value(buffer: &[u8], pos: usize) -> Result<Option<(u32, usize)>, ParseError> {
+    let Some(&first) = buffer.get(pos) else {
+        return Ok(None);
+    };
+    let len = match first {
+        0x00..=0x7F => 1,
+        0xC0..=0xDF => 2,
+        0xE0..=0xEF => 3,
+        _ => return Err(invalid_utf8_error(buffer, pos)),
+    };
+    let Some(bytes) = buffer.get(pos..pos + len) else {
+        return Ok(None);
+    };
+    let c = std::str::from_utf8(bytes)
+        .ok()
+        .and_then(|s| s.chars().next())
+        .ok_or_else(|| invalid_utf8_error(buffer, pos))?;
+    Ok(Some((c as u32, len)))
 }
 
-pub(crate) fn parse_csi_sgr_mouse(buffer: &[u8]) -> io::Result<Option<Event>> {
+pub(crate) fn parse_csi_sgr_mouse(
+    buffer: &[u8],
+    options: ParserOptions,
+) -> Result<Option<Event>, ParseError> {
     // ESC [ < Cb ; Cx ; Cy (;) (M or m)
 
     assert!(buffer.starts_with(b"\x1B[<")); // ESC [ <
@@ -688,17 +1108,17 @@ pub(crate) fn parse_csi_sgr_mouse(buffer: &[u8]) -> io::Result<Option<Event>> {
     }
 
     let s = std::str::from_utf8(&buffer[3..buffer.len() - 1])
-        .map_err(|_| could_not_parse_event_error())?;
+        .map_err(|e| invalid_utf8_error(buffer, 3 + e.valid_up_to()))?;
     let mut split = s.split(';');
 
-    let cb = next_parsed::<u8>(&mut split)?;
-    let (kind, modifiers) = parse_cb(cb)?;
+    let cb = next_parsed::<u8>(&mut split, buffer)?;
+    let (kind, modifiers) = parse_cb(cb, buffer)?;
 
     // See http://www.xfree86.org/current/ctlseqs.html#Mouse%20Tracking
     // The upper left character position on the terminal is denoted as 1,1.
     // Subtract 1 to keep it synced with cursor
-    let cx = next_parsed::<u16>(&mut split)? - 1;
-    let cy = next_parsed::<u16>(&mut split)? - 1;
+    let cx = next_parsed::<u16>(&mut split, buffer)?.saturating_sub(1);
+    let cy = next_parsed::<u16>(&mut split, buffer)?.saturating_sub(1);
 
     // When button 3 in Cb is used to represent mouse release, you can't tell which button was
     // released. SGR mode solves this by having the sequence end with a lowercase m if it's a
@@ -715,12 +1135,27 @@ pub(crate) fn parse_csi_sgr_mouse(buffer: &[u8]) -> io::Result<Option<Event>> {
         kind
     };
 
-    Ok(Some(Event::Mouse(MouseEvent {
-        kind,
-        column: cx,
-        row: cy,
-        modifiers,
-    })))
+    // In SGR-Pixels mode (1016), the coordinates are pixels instead of cells, but they're still
+    // reported relative to 1,1
+    let unit = if options.sgr_pixels {
+        PositionUnit::Pixels
+    } else {
+        PositionUnit::Cells
+    };
+
+    Ok(Some(Event::Mouse(
+        MouseEvent::new(kind, cx, cy)
+            .modifiers(modifiers)
+            .unit(unit),
+    )))
+}
+
+fn extra_mouse_button(button_number: u8) -> MouseButton {
+    match button_number {
+        8 => MouseButton::Back,
+        9 => MouseButton::Forward,
+        n => MouseButton::Other(n),
+    }
 }
 
 /// Cb is the byte of a mouse input that contains the button being used, the key modifiers being
@@ -736,7 +1171,7 @@ pub(crate) fn parse_csi_sgr_mouse(buffer: &[u8]) -> io::Result<Option<Event>> {
 /// - mouse is dragging
 /// - button number
 /// - button number
-fn parse_cb(cb: u8) -> io::Result<(MouseEventKind, KeyModifiers)> {
+fn parse_cb(cb: u8, buffer: &[u8]) -> Result<(MouseEventKind, KeyModifiers), ParseError> {
     let button_number = (cb & 0b0000_0011) | ((cb & 0b1100_0000) >> 4);
     let dragging = cb & 0b0010_0000 == 0b0010_0000;
 
@@ -753,8 +1188,17 @@ fn parse_cb(cb: u8) -> io::Result<(MouseEventKind, KeyModifiers)> {
         (5, false) => MouseEventKind::Scroll(ScrollDirection::Down),
         (6, false) => MouseEventKind::Scroll(ScrollDirection::Left),
         (7, false) => MouseEventKind::Scroll(ScrollDirection::Right),
+        // Buttons 8 through 11 match the X11 button numbers
+        (8..=11, false) => MouseEventKind::Down(extra_mouse_button(button_number)),
+        (8..=11, true) => MouseEventKind::Drag(extra_mouse_button(button_number)),
         // We do not support other buttons.
-        _ => return Err(could_not_parse_event_error()),
+        _ => {
+            return Err(ParseError::new(
+                ParseErrorKind::UnsupportedMouseButton,
+                buffer,
+                2,
+            ));
+        }
     };
 
     let mut modifiers = KeyModifiers::empty();
@@ -772,7 +1216,302 @@ fn parse_cb(cb: u8) -> io::Result<(MouseEventKind, KeyModifiers)> {
     Ok((kind, modifiers))
 }
 
-pub(crate) fn parse_csi_bracketed_paste(buffer: &[u8]) -> io::Result<Option<Event>> {
+pub(crate) fn parse_osc(
+    buffer: &[u8],
+    input_available: bool,
+    options: ParserOptions,
+) -> Result<Option<Event>, ParseError> {
+    // ESC ] Ps ; Pt BEL
+    // ESC ] Ps ; Pt ESC \
+    assert!(buffer.starts_with(b"\x1B]")); // ESC ]
+
+    if buffer.len() == 2 {
+        return if input_available {
+            Ok(None)
+        } else {
+            parse_control_string_introducer(buffer, options)
+        };
+    }
+
+    let params = match control_string_contents(buffer, true, options, is_osc_prefix)? {
+        ControlString::Complete(params) => params,
+        ControlString::Incomplete => return Ok(None),
+        ControlString::Invalid => return parse_control_string_introducer(buffer, options),
+    };
+
+    let mut split = params.splitn(2, |b| *b == b';');
+    let command = split.next().unwrap_or_default();
+    let data = split.next().unwrap_or_default();
+    match command {
+        b"4" => parse_osc_palette_color(buffer, data),
+        b"10" => parse_osc_color(buffer, ColorTarget::Foreground, data),
+        b"11" => parse_osc_color(buffer, ColorTarget::Background, data),
+        b"12" => parse_osc_color(buffer, ColorTarget::Cursor, data),
+        b"52" => parse_osc_clipboard(buffer, data),
+        _ => Err(ParseError::new(ParseErrorKind::UnknownSequence, buffer, 2)),
+    }
+}
+
+/// The state of a control string (such as OSC or DCS) in the input buffer.
+enum ControlString<'a> {
+    /// The contents between the two-byte introducer and the string terminator.
+    Complete(&'a [u8]),
+    /// The terminator hasn't arrived yet.
+    Incomplete,
+    /// The input can't be a control string, so the introducer was typed as a key with Alt.
+    Invalid,
+}
+
+/// Splits a control string into its contents.
+///
+/// Control strings are terminated by `ESC \`. Some, like OSC, may also be terminated by BEL.
+///
+/// The introducer is also what the terminal sends when a key is pressed with Alt, so an
+/// incomplete string is rejected as soon as it can't be the start of a reply that `is_prefix`
+/// accepts, or once it grows past [`ParserOptions::max_control_string_len`]. Otherwise, the
+/// keys typed afterwards would be swallowed until a terminator arrives.
+fn control_string_contents(
+    buffer: &[u8],
+    allow_bel: bool,
+    options: ParserOptions,
+    is_prefix: fn(&[u8]) -> bool,
+) -> Result<ControlString<'_>, ParseError> {
+    if allow_bel && let Some(contents) = buffer.strip_suffix(b"\x07") {
+        Ok(ControlString::Complete(&contents[2..]))
+    } else if let Some(contents) = buffer.strip_suffix(b"\x1B\\") {
+        Ok(ControlString::Complete(&contents[2..]))
+    } else if let Some(pos) = buffer[2..buffer.len() - 1]
+        .iter()
+        .position(|b| *b == b'\x1B')
+    {
+        // An escape byte that isn't part of the string terminator means this isn't a valid
+        // control string
+        Err(ParseError::new(
+            ParseErrorKind::UnknownSequence,
+            buffer,
+            pos + 2,
+        ))
+    } else {
+        let contents = buffer[2..].strip_suffix(b"\x1B").unwrap_or(&buffer[2..]);
+        if contents.iter().any(|b| !matches!(b, 0x20..=0x7E))
+            || contents.len() > options.max_control_string_len
+            || !is_prefix(contents)
+        {
+            Ok(ControlString::Invalid)
+        } else {
+            Ok(ControlString::Incomplete)
+        }
+    }
+}
+
+/// Parses the two-byte introducer of a control string as the key that was typed with Alt.
+///
+/// The stateless parser only returns the first event, so any input after the introducer is
+/// ignored. [`Parser`] parses it separately.
+fn parse_control_string_introducer(
+    buffer: &[u8],
+    options: ParserOptions,
+) -> Result<Option<Event>, ParseError> {
+    parse_event(&buffer[1..2], false, options).map(|event_option| event_option.map(add_alt))
+}
+
+fn is_osc_prefix(contents: &[u8]) -> bool {
+    // Replies start with a numeric command followed by a semicolon
+    match contents.iter().position(|b| !b.is_ascii_digit()) {
+        Some(pos) => pos > 0 && contents[pos] == b';',
+        None => true,
+    }
+}
+
+fn is_dcs_prefix(contents: &[u8]) -> bool {
+    [b">|".as_slice(), b"1+r", b"0+r"]
+        .iter()
+        .any(|prefix| contents.starts_with(prefix) || prefix.starts_with(contents))
+}
+
+fn parse_osc_palette_color(buffer: &[u8], data: &[u8]) -> Result<Option<Event>, ParseError> {
+    // ESC ] 4 ; c ; spec ST
+    //   c - palette index
+    let mut split = data.splitn(2, |b| *b == b';');
+    let index = std::str::from_utf8(split.next().unwrap_or_default())
+        .ok()
+        .and_then(|index| index.parse::<u8>().ok())
+        .ok_or_else(|| malformed_parameters_error(buffer))?;
+    let spec = split
+        .next()
+        .ok_or_else(|| malformed_parameters_error(buffer))?;
+    parse_osc_color(buffer, ColorTarget::Palette(index), spec)
+}
+
+fn parse_osc_color(
+    buffer: &[u8],
+    target: ColorTarget,
+    spec: &[u8],
+) -> Result<Option<Event>, ParseError> {
+    // ESC ] Ps ; rgb:r/g/b ST
+    //   Each component has 1-4 hex digits
+    let components = spec
+        .strip_prefix(b"rgb:")
+        .ok_or_else(|| malformed_parameters_error(buffer))?;
+    let mut split = components.split(|b| *b == b'/');
+    let mut next_component = || {
+        split
+            .next()
+            .and_then(parse_color_component)
+            .ok_or_else(|| malformed_parameters_error(buffer))
+    };
+    let color = RgbColor::new(next_component()?, next_component()?, next_component()?);
+    if split.next().is_some() {
+        return Err(malformed_parameters_error(buffer));
+    }
+
+    Ok(Some(Event::Reply(TerminalReply::Color { target, color })))
+}
+
+fn parse_color_component(component: &[u8]) -> Option<u16> {
+    if component.is_empty() || component.len() > 4 {
+        return None;
+    }
+    let value = u32::from_str_radix(std::str::from_utf8(component).ok()?, 16).ok()?;
+    // Scale the value to 16 bits so "f", "ff", and "ffff" all represent the same intensity
+    let max = (1u32 << (4 * component.len())) - 1;
+    Some((value * 0xFFFF / max) as u16)
+}
+
+fn parse_osc_clipboard(buffer: &[u8], data: &[u8]) -> Result<Option<Event>, ParseError> {
+    // ESC ] 52 ; Pc ; Pd ST
+    //   Pc - selection targets
+    //   Pd - base64-encoded selection data
+    let mut split = data.splitn(2, |b| *b == b';');
+    let targets = split.next().unwrap_or_default();
+    let encoded = split
+        .next()
+        .ok_or_else(|| malformed_parameters_error(buffer))?;
+
+    // If multiple targets are given, the first one is the one that was read
+    let selection = match targets.first() {
+        None | Some(b's') => ClipboardSelection::Select,
+        Some(b'c') => ClipboardSelection::Clipboard,
+        Some(b'p') => ClipboardSelection::Primary,
+        Some(b'q') => ClipboardSelection::Secondary,
+        Some(val @ b'0'..=b'7') => ClipboardSelection::CutBuffer(val - b'0'),
+        Some(_) => return Err(malformed_parameters_error(buffer)),
+    };
+    let data = decode_base64(encoded).ok_or_else(|| malformed_parameters_error(buffer))?;
+
+    Ok(Some(Event::Reply(TerminalReply::Clipboard {
+        selection,
+        data,
+    })))
+}
+
+fn decode_base64(input: &[u8]) -> Option<Vec<u8>> {
+    let input = input
+        .strip_suffix(b"==")
+        .or_else(|| input.strip_suffix(b"="))
+        .unwrap_or(input);
+    let mut decoded = Vec::with_capacity(input.len() * 3 / 4);
+    let mut acc = 0u32;
+    let mut bits = 0;
+    for byte in input {
+        let value = match byte {
+            b'A'..=b'Z' => byte - b'A',
+            b'a'..=b'z' => byte - b'a' + 26,
+            b'0'..=b'9' => byte - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            _ => return None,
+        };
+        acc = (acc << 6) | u32::from(value);
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            decoded.push((acc >> bits) as u8);
+            acc &= (1 << bits) - 1;
+        }
+    }
+    Some(decoded)
+}
+
+pub(crate) fn parse_dcs(
+    buffer: &[u8],
+    input_available: bool,
+    options: ParserOptions,
+) -> Result<Option<Event>, ParseError> {
+    // ESC P Pt ESC \
+    assert!(buffer.starts_with(b"\x1BP")); // ESC P
+
+    if buffer.len() == 2 {
+        return if input_available {
+            Ok(None)
+        } else {
+            parse_control_string_introducer(buffer, options)
+        };
+    }
+
+    let contents = match control_string_contents(buffer, false, options, is_dcs_prefix)? {
+        ControlString::Complete(contents) => contents,
+        ControlString::Incomplete => return Ok(None),
+        ControlString::Invalid => return parse_control_string_introducer(buffer, options),
+    };
+
+    if let Some(name) = contents.strip_prefix(b">|") {
+        // ESC P > | name ESC \
+        let name = std::str::from_utf8(name)
+            .map_err(|e| invalid_utf8_error(buffer, 4 + e.valid_up_to()))?;
+        Ok(Some(Event::Reply(TerminalReply::TerminalVersion(
+            name.to_string(),
+        ))))
+    } else if let Some(capabilities) = contents.strip_prefix(b"1+r") {
+        parse_dcs_capabilities(buffer, true, capabilities)
+    } else if let Some(capabilities) = contents.strip_prefix(b"0+r") {
+        parse_dcs_capabilities(buffer, false, capabilities)
+    } else {
+        Err(ParseError::new(ParseErrorKind::UnknownSequence, buffer, 2))
+    }
+}
+
+fn parse_dcs_capabilities(
+    buffer: &[u8],
+    valid: bool,
+    data: &[u8],
+) -> Result<Option<Event>, ParseError> {
+    // ESC P 1 + r name=value ; name=value ... ESC \
+    // ESC P 0 + r name ; name ... ESC \
+    //   Names and values are hex-encoded
+    let decode = |hex: &[u8]| {
+        decode_hex(hex)
+            .and_then(|decoded| String::from_utf8(decoded).ok())
+            .ok_or_else(|| malformed_parameters_error(buffer))
+    };
+    let capabilities = data
+        .split(|b| *b == b';')
+        .filter(|capability| !capability.is_empty())
+        .map(|capability| {
+            let mut split = capability.splitn(2, |b| *b == b'=');
+            let name = decode(split.next().unwrap_or_default())?;
+            let value = split.next().map(decode).transpose()?;
+            Ok((name, value))
+        })
+        .collect::<Result<_, _>>()?;
+
+    Ok(Some(Event::Reply(TerminalReply::Capabilities {
+        valid,
+        capabilities,
+    })))
+}
+
+fn decode_hex(input: &[u8]) -> Option<Vec<u8>> {
+    if !input.len().is_multiple_of(2) || !input.iter().all(u8::is_ascii_hexdigit) {
+        return None;
+    }
+    input
+        .chunks(2)
+        .map(|pair| u8::from_str_radix(std::str::from_utf8(pair).ok()?, 16).ok())
+        .collect()
+}
+
+pub(crate) fn parse_csi_bracketed_paste(buffer: &[u8]) -> Result<Option<Event>, ParseError> {
     // ESC [ 2 0 0 ~ pasted text ESC 2 0 1 ~
     assert!(buffer.starts_with(b"\x1B[200~"));
 
@@ -784,10 +1523,13 @@ pub(crate) fn parse_csi_bracketed_paste(buffer: &[u8]) -> io::Result<Option<Even
     }
 }
 
-pub(crate) fn parse_utf8_char(buffer: &[u8]) -> io::Result<Option<char>> {
+pub(crate) fn parse_utf8_char(buffer: &[u8]) -> Result<Option<char>, ParseError> {
     match std::str::from_utf8(buffer) {
         Ok(s) => {
-            let ch = s.chars().next().ok_or_else(could_not_parse_event_error)?;
+            let ch = s
+                .chars()
+                .next()
+                .ok_or_else(|| invalid_utf8_error(buffer, 0))?;
 
             Ok(Some(ch))
         }
@@ -801,14 +1543,14 @@ pub(crate) fn parse_utf8_char(buffer: &[u8]) -> io::Result<Option<char>> {
                 (0xC0..=0xDF) => 2, // 110xxxxx 10xxxxxx
                 (0xE0..=0xEF) => 3, // 1110xxxx 10xxxxxx 10xxxxxx
                 (0xF0..=0xF7) => 4, // 11110xxx 10xxxxxx 10xxxxxx 10xxxxxx
-                (0x80..=0xBF) | (0xF8..=0xFF) => return Err(could_not_parse_event_error()),
+                (0x80..=0xBF) | (0xF8..=0xFF) => return Err(invalid_utf8_error(buffer, 0)),
             };
 
             // More than 1 byte, check them for 10xxxxxx pattern
             if required_bytes > 1 && buffer.len() > 1 {
-                for byte in &buffer[1..] {
+                for (i, byte) in buffer.iter().enumerate().skip(1) {
                     if byte & !0b0011_1111 != 0b1000_0000 {
-                        return Err(could_not_parse_event_error());
+                        return Err(invalid_utf8_error(buffer, i));
                     }
                 }
             }
@@ -817,7 +1559,7 @@ pub(crate) fn parse_utf8_char(buffer: &[u8]) -> io::Result<Option<char>> {
                 // All bytes looks good so far, but we need more of them
                 Ok(None)
             } else {
-                Err(could_not_parse_event_error())
+                Err(invalid_utf8_error(buffer, 0))
             }
         }
     }
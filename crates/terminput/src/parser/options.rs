@@ -0,0 +1,167 @@
+/// Options that control how input is parsed.
+///
+/// Some terminal modes change the meaning of sequences that would otherwise look identical, so the
+/// parser needs to be told which modes are active.
+///
+/// # Example
+///
+/// ```
+/// use terminput::{Event, ParserOptions};
+///
+/// let options = ParserOptions::new().sgr_pixels(true);
+/// let event = Event::parse_with_options(b"\x1B[<0;125;380M", options).unwrap();
+/// assert!(event.is_some());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ParserOptions {
+    /// Treat SGR mouse coordinates as pixels instead of cells. This should be enabled when
+    /// SGR-Pixels mouse mode (mode 1016) is active.
+    pub sgr_pixels: bool,
+    /// Parse the coordinates in normal mouse reports (`CSI M Cb Cx Cy`) as UTF-8 encoded
+    /// characters. This should be enabled when UTF-8 mouse mode (mode 1005) is active.
+    pub utf8_mouse: bool,
+    /// Recognize the 8-bit C1 control introducers for CSI (`0x9B`) and SS3 (`0x8F`). These bytes
+    /// conflict with UTF-8 input, so this should only be enabled when the terminal sends 8-bit
+    /// controls.
+    pub eight_bit_controls: bool,
+    /// Parse `0x08` as Backspace instead of Ctrl+H. This should be enabled when the terminal's
+    /// Backspace key sends `^H`, such as xterm with `backarrowKey` disabled. `0x7F` is always
+    /// parsed as Backspace.
+    pub ctrl_h_as_backspace: bool,
+    /// Parse `\n` as Enter instead of Ctrl+J. This should be enabled when the terminal translates
+    /// carriage returns into newlines (the `ICRNL` termios flag), which is usually only the case
+    /// outside of raw mode.
+    pub newline_as_enter: bool,
+    /// Parse `\t` as Ctrl+I instead of Tab.
+    pub tab_as_ctrl_i: bool,
+    /// Parse `0x1C`-`0x1F` as Ctrl+`\`, Ctrl+`]`, Ctrl+`^` and Ctrl+`_`, which is how they're
+    /// defined in ASCII, instead of Ctrl+4 through Ctrl+7, which is how xterm-style terminals
+    /// produce them on a US keyboard.
+    pub ascii_control_punctuation: bool,
+    /// Parse bytes `0x80`-`0xFF` as Alt plus the character in the lower 7 bits, instead of as
+    /// UTF-8. This should be enabled when the terminal sends Alt by setting the high bit, such as
+    /// xterm with `eightBitInput` enabled. [`ParserOptions::eight_bit_controls`] takes precedence
+    /// for the bytes it recognizes.
+    pub eight_bit_meta: bool,
+    /// Always parse `CSI 1 ; n R` as a cursor position report. Otherwise, it's parsed as F3 with
+    /// modifiers when `n` is in the range xterm uses for modifiers, which is also a cursor position
+    /// report for columns 2-16 of the first row. This should be enabled while waiting for a reply
+    /// to a cursor position request (`CSI 6 n`).
+    pub cursor_position_reports: bool,
+    /// The maximum number of bytes in an OSC or DCS reply before it's treated as invalid input.
+    /// Clipboard replies may need a larger limit if large amounts of data are copied.
+    pub max_control_string_len: usize,
+}
+
+impl Default for ParserOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ParserOptions {
+    /// Creates a new [`ParserOptions`] with the default settings.
+    pub const fn new() -> Self {
+        Self {
+            sgr_pixels: false,
+            utf8_mouse: false,
+            eight_bit_controls: false,
+            ctrl_h_as_backspace: false,
+            newline_as_enter: false,
+            tab_as_ctrl_i: false,
+            ascii_control_punctuation: false,
+            eight_bit_meta: false,
+            cursor_position_reports: false,
+            max_control_string_len: 1 << 16,
+        }
+    }
+
+    /// Sets whether SGR mouse coordinates are parsed as pixels.
+    pub const fn sgr_pixels(mut self, sgr_pixels: bool) -> Self {
+        self.sgr_pixels = sgr_pixels;
+        self
+    }
+
+    /// Sets whether normal mouse reports are parsed using UTF-8 mouse mode.
+    pub const fn utf8_mouse(mut self, utf8_mouse: bool) -> Self {
+        self.utf8_mouse = utf8_mouse;
+        self
+    }
+
+    /// Sets whether 8-bit C1 control introducers are recognized.
+    pub const fn eight_bit_controls(mut self, eight_bit_controls: bool) -> Self {
+        self.eight_bit_controls = eight_bit_controls;
+        self
+    }
+
+    /// Sets whether `0x08` is parsed as Backspace instead of Ctrl+H.
+    pub const fn ctrl_h_as_backspace(mut self, ctrl_h_as_backspace: bool) -> Self {
+        self.ctrl_h_as_backspace = ctrl_h_as_backspace;
+        self
+    }
+
+    /// Sets whether `\n` is parsed as Enter instead of Ctrl+J.
+    pub const fn newline_as_enter(mut self, newline_as_enter: bool) -> Self {
+        self.newline_as_enter = newline_as_enter;
+        self
+    }
+
+    /// Sets whether `\t` is parsed as Ctrl+I instead of Tab.
+    pub const fn tab_as_ctrl_i(mut self, tab_as_ctrl_i: bool) -> Self {
+        self.tab_as_ctrl_i = tab_as_ctrl_i;
+        self
+    }
+
+    /// Sets whether `0x1C`-`0x1F` are parsed using their ASCII control character names.
+    pub const fn ascii_control_punctuation(mut self, ascii_control_punctuation: bool) -> Self {
+        self.ascii_control_punctuation = ascii_control_punctuation;
+        self
+    }
+
+    /// Sets whether bytes with the high bit set are parsed as Alt plus a 7-bit character.
+    pub const fn eight_bit_meta(mut self, eight_bit_meta: bool) -> Self {
+        self.eight_bit_meta = eight_bit_meta;
+        self
+    }
+
+    /// Sets whether ambiguous sequences are parsed as cursor position reports instead of F3.
+    pub const fn cursor_position_reports(mut self, cursor_position_reports: bool) -> Self {
+        self.cursor_position_reports = cursor_position_reports;
+        self
+    }
+
+    /// Sets the maximum number of bytes in an OSC or DCS reply.
+    pub const fn max_control_string_len(mut self, max_control_string_len: usize) -> Self {
+        self.max_control_string_len = max_control_string_len;
+        self
+    }
+
+    /// Configures the Backspace mapping from the terminal's erase character, which is the
+    /// `VERASE` entry in the termios control characters. This allows Backspace to be recognized
+    /// without any user configuration.
+    ///
+    /// Only `^H` (`0x08`) and `DEL` (`0x7F`) are recognized as erase characters, since those are
+    /// the only bytes a terminal's Backspace key sends. Any other value leaves `0x08` parsed as
+    /// Ctrl+H. The remaining termios control characters, such as `VINTR`, `VQUIT`, `VEOF` and
+    /// `VSUSP`, don't affect parsing. They're signals to the line discipline rather than keys, so
+    /// the bytes are still parsed as the Ctrl key that produced them (e.g. `0x03` is Ctrl+C) no
+    /// matter which control character they're assigned to.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use terminput::{Event, KeyCode, ParserOptions};
+    ///
+    /// // Erase character read from `termios.c_cc[VERASE]`
+    /// let verase = 0x08;
+    /// let options = ParserOptions::new().erase_char(verase);
+    /// assert_eq!(
+    ///     Event::parse_with_options(b"\x08", options).unwrap(),
+    ///     Some(Event::Key(KeyCode::Backspace.into()))
+    /// );
+    /// ```
+    pub const fn erase_char(mut self, erase_char: u8) -> Self {
+        self.ctrl_h_as_backspace = erase_char == 0x08;
+        self
+    }
+}
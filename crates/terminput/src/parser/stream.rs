@@ -0,0 +1,428 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+use std::vec::Vec;
+
+use super::{ParseError, ParserOptions, parse_event};
+use crate::{Backend, Event, EventSource, KeyCode, KeyModifiers, TimedEvent};
+
+/// Source of the current time used to resolve escape timeouts.
+///
+/// The default [`SystemClock`] should be used in most cases. A custom implementation can be used to
+/// control the passage of time in tests.
+pub trait Clock {
+    /// Returns the current time.
+    fn now(&self) -> Instant;
+}
+
+impl<T> Clock for &T
+where
+    T: Clock + ?Sized,
+{
+    fn now(&self) -> Instant {
+        (**self).now()
+    }
+}
+
+/// [`Clock`] implementation that uses the system's monotonic clock.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+// A flushed result along with the raw bytes of its sequence
+type Flushed = (Result<(Event, usize), ParseError>, Vec<u8>);
+
+/// A stateful parser for reading events from a stream of bytes.
+///
+/// Unlike [`Event::parse_from`], which expects a buffer containing a single event, the parser
+/// accepts arbitrary chunks of input (such as the result of each `read()` call on a raw TTY).
+/// Sequences that are split across multiple chunks are buffered until the rest of the sequence
+/// arrives, and chunks that contain several sequences will produce several events.
+///
+/// # Example
+///
+/// ```
+/// use terminput::{Event, KeyCode, Parser};
+///
+/// let mut parser = Parser::new();
+/// let events: Vec<_> = parser.parse(b"a\x1B[A").collect::<Result<_, _>>().unwrap();
+/// assert_eq!(
+///     events,
+///     [
+///         (Event::Key(KeyCode::Char('a').into()), 1),
+///         (Event::Key(KeyCode::Up.into()), 3),
+///     ]
+/// );
+///
+/// // Incomplete sequences are completed by subsequent calls
+/// assert_eq!(parser.parse(b"\x1B[1;5").count(), 0);
+/// assert_eq!(parser.parse(b"D").count(), 1);
+/// ```
+///
+/// # Escape timeout
+///
+/// An escape byte at the end of the input could either be the Esc key or the start of a sequence
+/// that hasn't finished arriving yet. By default, the parser assumes the former. If an
+/// [escape timeout](Parser::escape_timeout) is configured, the parser waits for more input
+/// instead. Once the timeout elapses, the pending bytes are flushed as key presses, so `ESC`
+/// becomes Esc, `ESC [` becomes Alt+`[`, `ESC O` becomes Alt+`O`, and so on. This is similar to
+/// vim's `ttimeoutlen` setting.
+///
+/// Use [`Parser::timeout`] to find out how long to wait for more input before calling
+/// [`Parser::flush_expired`].
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use terminput::{Event, KeyCode, Parser};
+///
+/// let mut parser = Parser::new().escape_timeout(Duration::from_millis(50));
+/// assert_eq!(parser.parse(b"\x1B").count(), 0);
+/// assert!(parser.timeout().is_some());
+///
+/// // The rest of the sequence arrives before the timeout
+/// let events: Vec<_> = parser.parse(b"[A").collect::<Result<_, _>>().unwrap();
+/// assert_eq!(events, [(Event::Key(KeyCode::Up.into()), 3)]);
+/// ```
+#[derive(Debug)]
+pub struct Parser<C = SystemClock> {
+    buffer: Vec<u8>,
+    queue: VecDeque<Flushed>,
+    replay: VecDeque<u8>,
+    last_raw: Vec<u8>,
+    clock: C,
+    epoch: Instant,
+    escape_timeout: Option<Duration>,
+    pending_since: Option<Instant>,
+    options: ParserOptions,
+}
+
+impl Parser {
+    /// Creates a new [`Parser`].
+    pub fn new() -> Self {
+        Self::with_clock(SystemClock)
+    }
+}
+
+impl Default for Parser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C> Parser<C>
+where
+    C: Clock,
+{
+    /// Creates a new [`Parser`] that uses the supplied [`Clock`] to resolve escape timeouts.
+    pub fn with_clock(clock: C) -> Self {
+        let epoch = clock.now();
+        Self {
+            buffer: Vec::new(),
+            queue: VecDeque::new(),
+            replay: VecDeque::new(),
+            last_raw: Vec::new(),
+            clock,
+            epoch,
+            escape_timeout: None,
+            pending_since: None,
+            options: ParserOptions::new(),
+        }
+    }
+
+    /// Sets the amount of time to wait for the rest of an escape sequence before treating the
+    /// pending bytes as individual key presses.
+    ///
+    /// See the [escape timeout](Parser#escape-timeout) section for details.
+    pub fn escape_timeout(mut self, timeout: Duration) -> Self {
+        self.escape_timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the [`ParserOptions`] used to parse each sequence.
+    pub fn options(mut self, options: ParserOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Updates the [`ParserOptions`] used to parse subsequent sequences. This can be used when
+    /// the application changes a terminal mode that affects parsing.
+    pub fn set_options(&mut self, options: ParserOptions) {
+        self.options = options;
+    }
+
+    /// Parses the next chunk of input.
+    ///
+    /// Returns an iterator over the events contained in the input, along with the number of
+    /// bytes that made up each event's sequence. A sequence may span multiple calls, so the
+    /// length can include bytes that were passed in previously.
+    ///
+    /// Any bytes that form an incomplete sequence at the end of the input are kept until the next
+    /// call. A lone escape byte at the end of the input is treated as the Esc key unless an
+    /// [escape timeout](Parser::escape_timeout) is configured.
+    ///
+    /// If the escape timeout has elapsed since the last call, any pending bytes are flushed before
+    /// the new input is parsed.
+    ///
+    /// The iterator yields a [`ParseError`] for each sequence that cannot be parsed into a valid
+    /// event. Afterwards, parsing resumes from the next escape byte within the invalid sequence, if
+    /// there is one, so that a malformed sequence doesn't swallow the sequences that follow it.
+    ///
+    /// If the iterator is dropped before it's exhausted, the rest of the input is kept and parsed
+    /// by the next call.
+    pub fn parse<'a>(&'a mut self, input: &'a [u8]) -> Events<'a, C> {
+        if self.is_expired() {
+            self.flush_pending();
+        }
+        Events {
+            parser: self,
+            input,
+            position: 0,
+        }
+    }
+
+    /// Parses the next chunk of input, like [`Parser::parse`], but wraps each event in a
+    /// [`TimedEvent`].
+    ///
+    /// The events are timestamped with the time this method was called, relative to
+    /// [`Parser::epoch`], and include the raw bytes of each event's sequence.
+    pub fn parse_timed<'a>(&'a mut self, input: &'a [u8]) -> TimedEvents<'a, C> {
+        let timestamp = self.clock.now().saturating_duration_since(self.epoch);
+        TimedEvents {
+            events: self.parse(input),
+            timestamp,
+        }
+    }
+
+    /// Returns the time the parser was created. Timestamps from [`Parser::parse_timed`] are
+    /// relative to this.
+    pub fn epoch(&self) -> Instant {
+        self.epoch
+    }
+
+    /// Returns the amount of time remaining until the pending escape sequence will be flushed, or
+    /// [`None`] if no escape sequence is waiting on more input.
+    ///
+    /// This is intended to be used as the timeout for polling the input source. If the timeout
+    /// elapses without receiving more input, call [`Parser::flush_expired`].
+    pub fn timeout(&self) -> Option<Duration> {
+        let deadline = self.deadline()?;
+        Some(deadline.saturating_duration_since(self.clock.now()))
+    }
+
+    /// Flushes the pending escape sequence if the escape timeout has elapsed.
+    ///
+    /// Returns an iterator over the flushed events, which is empty if the timeout hasn't elapsed
+    /// yet.
+    pub fn flush_expired(&mut self) -> Events<'_, C> {
+        self.parse(&[])
+    }
+
+    /// Flushes any pending escape sequence regardless of the escape timeout.
+    ///
+    /// This can be used when the input source is closed.
+    pub fn flush(&mut self) -> Events<'_, C> {
+        if is_flushable(&self.buffer) {
+            self.flush_pending();
+        }
+        Events {
+            parser: self,
+            input: &[],
+            position: 0,
+        }
+    }
+
+    /// Returns the bytes of the incomplete sequence that's waiting on more input, if any.
+    pub fn pending(&self) -> &[u8] {
+        &self.buffer
+    }
+
+    /// Discards any buffered input.
+    pub fn clear(&mut self) {
+        self.buffer.clear();
+        self.queue.clear();
+        self.replay.clear();
+        self.pending_since = None;
+    }
+
+    fn deadline(&self) -> Option<Instant> {
+        if !is_flushable(&self.buffer) {
+            return None;
+        }
+        Some(self.pending_since? + self.escape_timeout?)
+    }
+
+    fn is_expired(&self) -> bool {
+        self.deadline()
+            .is_some_and(|deadline| self.clock.now() >= deadline)
+    }
+
+    fn flush_pending(&mut self) {
+        let pending = std::mem::take(&mut self.buffer);
+        self.pending_since = None;
+        if pending.len() == 1 {
+            self.queue
+                .push_back((Ok((Event::Key(KeyCode::Esc.into()), 1)), pending));
+            return;
+        }
+
+        // The escape byte is treated as an alt prefix for the next event and the remaining bytes
+        // are parsed as though no more input is coming.
+        let mut start = 1;
+        for end in 2..=pending.len() {
+            match parse_event(&pending[start..end], end < pending.len(), self.options) {
+                Ok(Some(mut event)) => {
+                    let mut len = end - start;
+                    if start == 1 {
+                        if let Event::Key(key_event) = &mut event {
+                            key_event.modifiers |= KeyModifiers::ALT;
+                        }
+                        len += 1;
+                    }
+                    self.queue
+                        .push_back((Ok((event, len)), pending[end - len..end].to_vec()));
+                    start = end;
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    self.queue.push_back((Err(e), pending[start..end].to_vec()));
+                    start = end;
+                }
+            }
+        }
+
+        if start == 1 {
+            // Nothing after the escape byte could be parsed yet
+            self.queue.push_back((
+                Ok((Event::Key(KeyCode::Esc.into()), 1)),
+                pending[..1].to_vec(),
+            ));
+        }
+        self.buffer.extend_from_slice(&pending[start..]);
+        if !self.buffer.is_empty() {
+            self.pending_since = Some(self.clock.now());
+        }
+    }
+}
+
+fn is_control_string_introducer_key(buffer: &[u8], event: &Event) -> bool {
+    // OSC and DCS sequences only produce replies, so a key means the introducer wasn't followed
+    // by a valid control string
+    (buffer.starts_with(b"\x1B]") || buffer.starts_with(b"\x1BP"))
+        && buffer.len() > 2
+        && matches!(event, Event::Key(_))
+}
+
+fn is_flushable(buffer: &[u8]) -> bool {
+    // Pastes may legitimately take a while to arrive, so they shouldn't be interrupted
+    buffer.first() == Some(&b'\x1B') && !buffer.starts_with(b"\x1B[200~")
+}
+
+/// Iterator over the events parsed from a chunk of input.
+///
+/// Created by [`Parser::parse`].
+#[derive(Debug)]
+pub struct Events<'a, C = SystemClock> {
+    parser: &'a mut Parser<C>,
+    input: &'a [u8],
+    position: usize,
+}
+
+impl<C> Iterator for Events<'_, C>
+where
+    C: Clock,
+{
+    type Item = Result<(Event, usize), ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some((result, raw)) = self.parser.queue.pop_front() {
+            self.parser.last_raw = raw;
+            return Some(result);
+        }
+
+        let wait_for_escape = self.parser.escape_timeout.is_some();
+        loop {
+            let parser = &mut *self.parser;
+            let byte = if let Some(byte) = parser.replay.pop_front() {
+                byte
+            } else if self.position < self.input.len() {
+                self.position += 1;
+                self.input[self.position - 1]
+            } else {
+                return None;
+            };
+            parser.buffer.push(byte);
+            let at_end = parser.replay.is_empty() && self.position == self.input.len();
+
+            match parse_event(&parser.buffer, !at_end || wait_for_escape, parser.options) {
+                Ok(Some(event)) => {
+                    if is_control_string_introducer_key(&parser.buffer, &event) {
+                        // The introducer was a key pressed with Alt, so the bytes that follow it
+                        // are parsed again
+                        for (i, byte) in parser.buffer.drain(2..).enumerate() {
+                            parser.replay.insert(i, byte);
+                        }
+                    }
+                    let len = parser.buffer.len();
+                    parser.last_raw.clear();
+                    parser.last_raw.append(&mut parser.buffer);
+                    parser.pending_since = None;
+                    return Some(Ok((event, len)));
+                }
+                Ok(None) => {
+                    if at_end {
+                        parser.pending_since = Some(parser.clock.now());
+                    }
+                }
+                Err(e) => {
+                    // Resync at the next byte that could start a new sequence
+                    if let Some(start) = parser.buffer.iter().skip(1).position(|b| *b == b'\x1B') {
+                        let rest = parser.buffer.drain(start + 1..);
+                        for (i, byte) in rest.enumerate() {
+                            parser.replay.insert(i, byte);
+                        }
+                    }
+                    parser.buffer.clear();
+                    parser.pending_since = None;
+                    return Some(Err(e));
+                }
+            }
+        }
+    }
+}
+
+impl<C> Drop for Events<'_, C> {
+    fn drop(&mut self) {
+        // Keep any input that wasn't read so it isn't lost
+        self.parser.replay.extend(&self.input[self.position..]);
+    }
+}
+
+/// Iterator over the timestamped events parsed from a chunk of input.
+///
+/// Created by [`Parser::parse_timed`].
+#[derive(Debug)]
+pub struct TimedEvents<'a, C = SystemClock> {
+    events: Events<'a, C>,
+    timestamp: Duration,
+}
+
+impl<C> Iterator for TimedEvents<'_, C>
+where
+    C: Clock,
+{
+    type Item = Result<TimedEvent, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (event, _) = match self.events.next()? {
+            Ok(result) => result,
+            Err(e) => return Some(Err(e)),
+        };
+        let source = EventSource::new(Backend::Parser).raw(self.events.parser.last_raw.as_slice());
+        Some(Ok(TimedEvent::new(event, self.timestamp, source)))
+    }
+}
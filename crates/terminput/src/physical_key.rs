@@ -0,0 +1,300 @@
+/// Generates the [`PhysicalKey`] enum along with the conversions to and from the W3C `code`
+/// strings, which are identical to the variant names.
+macro_rules! physical_keys {
+    ($($(#[$attr:meta])* $variant:ident,)*) => {
+        /// The physical location of a key on the keyboard, independent of the keyboard layout.
+        ///
+        /// The variants are named after the W3C [`KeyboardEvent.code`] values, which identify keys
+        /// by their position on a standard US keyboard. For example, [`PhysicalKey::KeyQ`] is the
+        /// key to the right of Tab, even if it produces `a` on an AZERTY layout.
+        ///
+        /// [`KeyboardEvent.code`]: https://www.w3.org/TR/uievents-code/
+        #[derive(Debug, PartialOrd, Ord, PartialEq, Eq, Clone, Copy, Hash)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+        pub enum PhysicalKey {
+            $(
+                #[doc = concat!("The `", stringify!($variant), "` key.")]
+                $(#[$attr])*
+                $variant,
+            )*
+        }
+
+        impl PhysicalKey {
+            /// Returns the [`PhysicalKey`] for the given W3C `code` value, or [`None`] if the code
+            /// isn't recognized.
+            ///
+            /// # Example
+            ///
+            /// ```
+            /// use terminput::PhysicalKey;
+            ///
+            /// assert_eq!(PhysicalKey::from_code("KeyQ"), Some(PhysicalKey::KeyQ));
+            /// assert_eq!(PhysicalKey::from_code("Unidentified"), None);
+            /// ```
+            pub fn from_code(code: &str) -> Option<Self> {
+                match code {
+                    $(stringify!($variant) => Some(Self::$variant),)*
+                    _ => None,
+                }
+            }
+
+            /// Returns the W3C `code` value for the key.
+            pub const fn code(&self) -> &'static str {
+                match self {
+                    $(Self::$variant => stringify!($variant),)*
+                }
+            }
+        }
+    };
+}
+
+physical_keys! {
+    // Writing system keys
+    Backquote,
+    Backslash,
+    BracketLeft,
+    BracketRight,
+    Comma,
+    Digit0,
+    Digit1,
+    Digit2,
+    Digit3,
+    Digit4,
+    Digit5,
+    Digit6,
+    Digit7,
+    Digit8,
+    Digit9,
+    Equal,
+    IntlBackslash,
+    IntlRo,
+    IntlYen,
+    KeyA,
+    KeyB,
+    KeyC,
+    KeyD,
+    KeyE,
+    KeyF,
+    KeyG,
+    KeyH,
+    KeyI,
+    KeyJ,
+    KeyK,
+    KeyL,
+    KeyM,
+    KeyN,
+    KeyO,
+    KeyP,
+    KeyQ,
+    KeyR,
+    KeyS,
+    KeyT,
+    KeyU,
+    KeyV,
+    KeyW,
+    KeyX,
+    KeyY,
+    KeyZ,
+    Minus,
+    Period,
+    Quote,
+    Semicolon,
+    Slash,
+
+    // Functional keys
+    AltLeft,
+    AltRight,
+    Backspace,
+    CapsLock,
+    ContextMenu,
+    ControlLeft,
+    ControlRight,
+    Enter,
+    MetaLeft,
+    MetaRight,
+    ShiftLeft,
+    ShiftRight,
+    Space,
+    Tab,
+    Convert,
+    KanaMode,
+    Lang1,
+    Lang2,
+    Lang3,
+    Lang4,
+    Lang5,
+    NonConvert,
+
+    // Control pad keys
+    Delete,
+    End,
+    Help,
+    Home,
+    Insert,
+    PageDown,
+    PageUp,
+
+    // Arrow keys
+    ArrowDown,
+    ArrowLeft,
+    ArrowRight,
+    ArrowUp,
+
+    // Numpad keys
+    NumLock,
+    Numpad0,
+    Numpad1,
+    Numpad2,
+    Numpad3,
+    Numpad4,
+    Numpad5,
+    Numpad6,
+    Numpad7,
+    Numpad8,
+    Numpad9,
+    NumpadAdd,
+    NumpadBackspace,
+    NumpadClear,
+    NumpadClearEntry,
+    NumpadComma,
+    NumpadDecimal,
+    NumpadDivide,
+    NumpadEnter,
+    NumpadEqual,
+    NumpadHash,
+    NumpadMemoryAdd,
+    NumpadMemoryClear,
+    NumpadMemoryRecall,
+    NumpadMemoryStore,
+    NumpadMemorySubtract,
+    NumpadMultiply,
+    NumpadParenLeft,
+    NumpadParenRight,
+    NumpadStar,
+    NumpadSubtract,
+
+    // Function keys
+    Escape,
+    F1,
+    F2,
+    F3,
+    F4,
+    F5,
+    F6,
+    F7,
+    F8,
+    F9,
+    F10,
+    F11,
+    F12,
+    F13,
+    F14,
+    F15,
+    F16,
+    F17,
+    F18,
+    F19,
+    F20,
+    F21,
+    F22,
+    F23,
+    F24,
+    Fn,
+    FnLock,
+    PrintScreen,
+    ScrollLock,
+    Pause,
+
+    // Media keys
+    BrowserBack,
+    BrowserFavorites,
+    BrowserForward,
+    BrowserHome,
+    BrowserRefresh,
+    BrowserSearch,
+    BrowserStop,
+    Eject,
+    LaunchApp1,
+    LaunchApp2,
+    LaunchMail,
+    MediaPlayPause,
+    MediaSelect,
+    MediaStop,
+    MediaTrackNext,
+    MediaTrackPrevious,
+    Power,
+    Sleep,
+    AudioVolumeDown,
+    AudioVolumeMute,
+    AudioVolumeUp,
+    WakeUp,
+
+    // Legacy editing keys
+    Again,
+    Copy,
+    Cut,
+    Find,
+    Open,
+    Paste,
+    Props,
+    Select,
+    Undo,
+}
+
+impl PhysicalKey {
+    /// Returns the key that produces the given character on a standard US keyboard layout without
+    /// any modifiers.
+    pub const fn from_us_layout_char(c: char) -> Option<Self> {
+        Some(match c {
+            'a' => Self::KeyA,
+            'b' => Self::KeyB,
+            'c' => Self::KeyC,
+            'd' => Self::KeyD,
+            'e' => Self::KeyE,
+            'f' => Self::KeyF,
+            'g' => Self::KeyG,
+            'h' => Self::KeyH,
+            'i' => Self::KeyI,
+            'j' => Self::KeyJ,
+            'k' => Self::KeyK,
+            'l' => Self::KeyL,
+            'm' => Self::KeyM,
+            'n' => Self::KeyN,
+            'o' => Self::KeyO,
+            'p' => Self::KeyP,
+            'q' => Self::KeyQ,
+            'r' => Self::KeyR,
+            's' => Self::KeyS,
+            't' => Self::KeyT,
+            'u' => Self::KeyU,
+            'v' => Self::KeyV,
+            'w' => Self::KeyW,
+            'x' => Self::KeyX,
+            'y' => Self::KeyY,
+            'z' => Self::KeyZ,
+            '0' => Self::Digit0,
+            '1' => Self::Digit1,
+            '2' => Self::Digit2,
+            '3' => Self::Digit3,
+            '4' => Self::Digit4,
+            '5' => Self::Digit5,
+            '6' => Self::Digit6,
+            '7' => Self::Digit7,
+            '8' => Self::Digit8,
+            '9' => Self::Digit9,
+            '`' => Self::Backquote,
+            '-' => Self::Minus,
+            '=' => Self::Equal,
+            '[' => Self::BracketLeft,
+            ']' => Self::BracketRight,
+            '\\' => Self::Backslash,
+            ';' => Self::Semicolon,
+            '\'' => Self::Quote,
+            ',' => Self::Comma,
+            '.' => Self::Period,
+            '/' => Self::Slash,
+            ' ' => Self::Space,
+            _ => return None,
+        })
+    }
+}
@@ -0,0 +1,160 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::KittyFlags;
+
+/// A reply sent by the terminal in response to a query.
+///
+/// Replies are sent on the same stream as user input, so they're parsed as events.
+#[derive(Debug, PartialOrd, PartialEq, Eq, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TerminalReply {
+    /// The cursor position report (CPR) sent in response to `CSI 6 n`.
+    ///
+    /// An xterm-style modified F3 key (`CSI 1 ; modifiers R`) can't be distinguished from a cursor
+    /// position report in the first row. These sequences are parsed as key events if the column
+    /// is a valid modifier value.
+    CursorPosition {
+        /// Zero-based row of the cursor.
+        row: u16,
+        /// Zero-based column of the cursor.
+        column: u16,
+    },
+    /// The primary device attributes (DA1) sent in response to `CSI c`. This contains the list of
+    /// attributes supported by the terminal. The first entry is typically the conformance level.
+    PrimaryDeviceAttributes(Vec<u16>),
+    /// The Kitty keyboard protocol flags that are currently enabled, sent in response to
+    /// `CSI ? u`.
+    KittyKeyboardFlags(KittyFlags),
+    /// The contents of a clipboard selection, sent in response to an OSC 52 query.
+    Clipboard {
+        /// The selection that was read.
+        selection: ClipboardSelection,
+        /// The decoded contents of the selection.
+        data: Vec<u8>,
+    },
+    /// A dynamic or palette color, sent in response to an OSC 10, 11, 12, or 4 query.
+    Color {
+        /// The color that was queried.
+        target: ColorTarget,
+        /// The value of the color.
+        color: RgbColor,
+    },
+    /// The state of a terminal mode, sent in response to a DECRQM query (`CSI ? Pm $ p` for DEC
+    /// private modes or `CSI Pm $ p` for ANSI modes).
+    ModeReport {
+        /// The mode that was queried.
+        mode: u16,
+        /// Whether the mode is a DEC private mode.
+        private: bool,
+        /// The state of the mode.
+        state: ModeState,
+    },
+    /// The name and version of the terminal, sent in response to an XTVERSION query (`CSI > q`).
+    TerminalVersion(String),
+    /// The values of terminfo capabilities, sent in response to an XTGETTCAP query
+    /// (`DCS + q Pt ST`).
+    Capabilities {
+        /// Whether the terminal recognized the requested capabilities. If not, the entries only
+        /// contain the names of the capabilities.
+        valid: bool,
+        /// The name and value of each capability. Boolean capabilities don't have a value.
+        capabilities: Vec<(String, Option<String>)>,
+    },
+}
+
+/// The state of a terminal mode reported by DECRPM.
+#[derive(Debug, PartialOrd, Ord, PartialEq, Eq, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ModeState {
+    /// The terminal doesn't recognize the mode.
+    NotRecognized,
+    /// The mode is set.
+    Set,
+    /// The mode is reset.
+    Reset,
+    /// The mode is set and can't be changed.
+    PermanentlySet,
+    /// The mode is reset and can't be changed.
+    PermanentlyReset,
+}
+
+impl ModeState {
+    /// Returns whether the mode is set, either temporarily or permanently.
+    pub const fn is_set(self) -> bool {
+        matches!(self, Self::Set | Self::PermanentlySet)
+    }
+
+    /// Returns whether the terminal supports changing the mode.
+    pub const fn is_changeable(self) -> bool {
+        matches!(self, Self::Set | Self::Reset)
+    }
+}
+
+/// A clipboard selection that can be accessed with OSC 52.
+#[derive(Debug, PartialOrd, Ord, PartialEq, Eq, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ClipboardSelection {
+    /// The system clipboard (`c`).
+    Clipboard,
+    /// The primary selection (`p`).
+    Primary,
+    /// The secondary selection (`q`).
+    Secondary,
+    /// The terminal's configured selection (`s`). This is also used if the reply doesn't specify
+    /// a selection.
+    Select,
+    /// One of the cut buffers (`0` through `7`).
+    CutBuffer(u8),
+}
+
+/// A terminal color that can be queried with an OSC sequence.
+#[derive(Debug, PartialOrd, Ord, PartialEq, Eq, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ColorTarget {
+    /// The default foreground color (OSC 10).
+    Foreground,
+    /// The default background color (OSC 11).
+    Background,
+    /// The cursor color (OSC 12).
+    Cursor,
+    /// A color from the 256-color palette (OSC 4).
+    Palette(u8),
+}
+
+/// An RGB color with 16 bits per component.
+///
+/// Terminals may report colors with fewer bits per component. These values are scaled to 16 bits,
+/// so `ff` is stored as `ffff`.
+#[derive(Debug, PartialOrd, Ord, PartialEq, Eq, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RgbColor {
+    /// Red component.
+    pub r: u16,
+    /// Green component.
+    pub g: u16,
+    /// Blue component.
+    pub b: u16,
+}
+
+impl RgbColor {
+    /// Creates a new [`RgbColor`].
+    pub const fn new(r: u16, g: u16, b: u16) -> Self {
+        Self { r, g, b }
+    }
+
+    /// Creates a new [`RgbColor`] from 8-bit components.
+    pub const fn from_rgb8(r: u8, g: u8, b: u8) -> Self {
+        // Multiplying by 0x101 repeats the byte, so 0xab becomes 0xabab
+        Self::new(r as u16 * 0x101, g as u16 * 0x101, b as u16 * 0x101)
+    }
+
+    /// Returns the components reduced to 8 bits.
+    pub const fn to_rgb8(self) -> (u8, u8, u8) {
+        (
+            (self.r >> 8) as u8,
+            (self.g >> 8) as u8,
+            (self.b >> 8) as u8,
+        )
+    }
+}
@@ -0,0 +1,84 @@
+use alloc::vec::Vec;
+use core::time::Duration;
+
+use crate::Event;
+
+/// An [`Event`] along with the time it was received and where it came from.
+///
+/// The timestamp is measured from a reference point chosen by the source, such as the time the
+/// [`Parser`](crate::Parser) was created or the browser's time origin. Timestamps are only
+/// comparable between events from the same source.
+///
+/// Some backends, such as crossterm and termion, don't record when events are received. Their
+/// `to_terminput_timed` conversions take the timestamp from the caller, which should read the clock
+/// as soon as the event is read so that the delay before conversion isn't included.
+#[derive(Debug, PartialOrd, PartialEq, Eq, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TimedEvent {
+    /// The event.
+    pub event: Event,
+    /// The time the event was received, relative to the source's reference point.
+    pub timestamp: Duration,
+    /// The source of the event.
+    pub source: EventSource,
+}
+
+impl TimedEvent {
+    /// Creates a new [`TimedEvent`].
+    pub fn new(event: impl Into<Event>, timestamp: Duration, source: EventSource) -> Self {
+        Self {
+            event: event.into(),
+            timestamp,
+            source,
+        }
+    }
+}
+
+/// Describes where a [`TimedEvent`] came from.
+#[derive(Debug, PartialOrd, PartialEq, Eq, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EventSource {
+    /// The backend that produced the event.
+    pub backend: Backend,
+    /// The raw bytes that were parsed into the event, if the backend has access to them.
+    pub raw: Option<Vec<u8>>,
+}
+
+impl EventSource {
+    /// Creates a new [`EventSource`] without any raw bytes.
+    pub const fn new(backend: Backend) -> Self {
+        Self { backend, raw: None }
+    }
+
+    /// Sets the raw bytes that were parsed into the event.
+    pub fn raw(mut self, raw: impl Into<Vec<u8>>) -> Self {
+        self.raw = Some(raw.into());
+        self
+    }
+}
+
+impl From<Backend> for EventSource {
+    fn from(value: Backend) -> Self {
+        Self::new(value)
+    }
+}
+
+/// The backend that produced an event.
+#[derive(Debug, PartialOrd, Ord, PartialEq, Eq, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Backend {
+    /// terminput's own [`Parser`](crate::Parser).
+    Parser,
+    /// The `crossterm` crate.
+    Crossterm,
+    /// The `termion` crate.
+    Termion,
+    /// The `termwiz` crate.
+    Termwiz,
+    /// The `termina` crate.
+    Termina,
+    /// The `egui` crate.
+    Egui,
+    /// The `web-sys` crate.
+    WebSys,
+}
@@ -64,3 +64,34 @@ fn test_match_with_compound_state() {
         key!(CAPS_NUM_LOCK, KeyModifiers::NONE, Char('c'))
     ));
 }
+
+#[test]
+fn test_keymap_lookup_ignores_reported_details() {
+    let mut keymap = std::collections::HashMap::new();
+    keymap.insert(KeyEvent::new(Char('c')).modifiers(CTRL), "copy");
+
+    let key_event = KeyEvent::new(Char('c'))
+        .modifiers(CTRL)
+        .base_layout_key('c')
+        .physical_key(terminput::PhysicalKey::KeyC);
+    assert_eq!(key_event, KeyEvent::new(Char('c')).modifiers(CTRL));
+    assert_eq!(keymap.get(&key_event), Some(&"copy"));
+}
+
+#[test]
+fn test_key_event_ordering_ignores_reported_details() {
+    let key_event = KeyEvent::new(Char('a'));
+    let with_text = KeyEvent::new(Char('a')).text(terminput::KeyText::from('ä'));
+    assert_eq!(key_event, with_text);
+    assert_eq!(key_event.cmp(&with_text), std::cmp::Ordering::Equal);
+    assert_eq!(
+        KeyEvent::new(Char('A')).cmp(&KeyEvent::new(Char('a')).modifiers(KeyModifiers::SHIFT)),
+        std::cmp::Ordering::Equal
+    );
+
+    let mut keymap = std::collections::BTreeMap::new();
+    keymap.insert(key_event, "first");
+    keymap.insert(with_text, "second");
+    assert_eq!(keymap.len(), 1);
+    assert_eq!(keymap.get(&key_event), Some(&"second"));
+}
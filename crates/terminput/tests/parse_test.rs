@@ -1,6 +1,9 @@
 use terminput::{
-    Encoding, Event, KeyCode, KeyEvent, KeyEventKind, KeyEventState, KeyModifiers, KittyFlags,
-    MediaKeyCode, ModifierDirection, ModifierKeyCode, MouseButton, MouseEvent, MouseEventKind,
+    ClipboardSelection, ColorTarget, Encoding, Event, ImeEvent, KeyCode, KeyEvent, KeyEventKind,
+    KeyEventState, KeyModifiers, KeySequenceFamily, KeyText, KittyFlags, MediaKeyCode, ModeState,
+    ModifierDirection, ModifierKeyCode, MouseButton, MouseEvent, MouseEventKind, ParseErrorKind,
+    ParserOptions, PhysicalKey, PositionUnit, RgbColor, ScrollAmount, ScrollDirection, ScrollUnit,
+    TerminalReply, XtermOptions,
 };
 
 #[test]
@@ -321,6 +324,13 @@ fn test_kitty_modifiers() {
     assert_eq!(buf[..written], *b"\x1B[57408;200u");
 }
 
+fn parse_key(sequence: &[u8]) -> KeyEvent {
+    match Event::parse_from(sequence).unwrap() {
+        Some(Event::Key(key_event)) => key_event,
+        event => panic!("expected a key event, got {event:?}"),
+    }
+}
+
 #[test]
 fn test_kitty_alternate_keys() {
     assert_eq!(
@@ -345,6 +355,136 @@ fn test_kitty_alternate_keys() {
         )
         .unwrap();
     assert_eq!(buf[..written], *b"\x1B[99;2u");
+
+    // Cyrillic layout, Ctrl+С on the physical C key
+    let key_event = parse_key(b"\x1B[1089::99;5u");
+    assert_eq!(
+        key_event,
+        KeyEvent::new(KeyCode::Char('с')).modifiers(KeyModifiers::CTRL)
+    );
+    assert_eq!(key_event.shifted_key, None);
+    assert_eq!(key_event.base_layout_key, Some('c'));
+    assert_eq!(key_event.physical_key, Some(PhysicalKey::KeyC));
+    let mut buf = [0; 32];
+    let written = Event::Key(key_event)
+        .encode(&mut buf, Encoding::Kitty(KittyFlags::all()))
+        .unwrap();
+    assert_eq!(buf[..written], *b"\x1B[1089::99;5u");
+
+    // Cyrillic layout, Shift+Ф on the physical A key
+    let key_event = parse_key(b"\x1B[1092:1060:97;2u");
+    assert_eq!(key_event, KeyEvent::new(KeyCode::Char('Ф')));
+    assert_eq!(key_event.shifted_key, Some('Ф'));
+    assert_eq!(key_event.unshifted_key, Some('ф'));
+    assert_eq!(key_event.base_layout_key, Some('a'));
+    assert_eq!(key_event.physical_key, Some(PhysicalKey::KeyA));
+    let event = Event::Key(key_event);
+    let written = event
+        .encode(&mut buf, Encoding::Kitty(KittyFlags::all()))
+        .unwrap();
+    assert_eq!(buf[..written], *b"\x1B[1092:1060:97;2u");
+
+    // Events created without the alternate keys still encode them
+    let written = Event::Key(
+        KeyEvent::new(KeyCode::Char('Ф'))
+            .shifted_key('Ф')
+            .base_layout_key('a'),
+    )
+    .encode(&mut buf, Encoding::Kitty(KittyFlags::all()))
+    .unwrap();
+    assert_eq!(buf[..written], *b"\x1B[1092:1060:97;2u");
+
+    // Alternate keys are omitted if the flag isn't set
+    let written = event
+        .encode(
+            &mut buf,
+            Encoding::Kitty(
+                KittyFlags::DISAMBIGUATE_ESCAPE_CODES | KittyFlags::REPORT_ALL_KEYS_AS_ESCAPE_CODES,
+            ),
+        )
+        .unwrap();
+    assert_eq!(buf[..written], *b"\x1B[1092;2u");
+}
+
+#[test]
+fn test_kitty_alternate_keys_round_trip() {
+    let mut buf = [0; 32];
+    for (sequence, code, unshifted_key) in [
+        (b"\x1B[49:33;2u".as_slice(), '!', '1'),
+        (b"\x1B[59:58;2u", ':', ';'),
+        (b"\x1B[47:63;2u", '?', '/'),
+        (b"\x1B[57:40;4u", '(', '9'),
+        (b"\x1B[99:67;2u", 'C', 'c'),
+    ] {
+        let key_event = parse_key(sequence);
+        assert_eq!(key_event.code, KeyCode::Char(code));
+        assert_eq!(key_event.shifted_key, Some(code));
+        assert_eq!(key_event.unshifted_key, Some(unshifted_key));
+        let written = Event::Key(key_event)
+            .encode(&mut buf, Encoding::Kitty(KittyFlags::all()))
+            .unwrap();
+        assert_eq!(buf[..written], *sequence);
+    }
+}
+
+#[test]
+fn test_kitty_associated_text() {
+    let key_event = parse_key(b"\x1B[97;;228u");
+    assert_eq!(key_event, KeyEvent::new(KeyCode::Char('a')));
+    assert_eq!(key_event.text, Some(KeyText::from('ä')));
+    let key_event = parse_key(b"\x1B[97;2;65u");
+    assert_eq!(
+        key_event,
+        KeyEvent::new(KeyCode::Char('a')).modifiers(KeyModifiers::SHIFT)
+    );
+    assert_eq!(key_event.text, Some(KeyText::from('A')));
+    let key_event = parse_key(b"\x1B[101;1;101:769u");
+    assert_eq!(key_event, KeyEvent::new(KeyCode::Char('e')));
+    assert_eq!(key_event.text, KeyText::new("e\u{301}"));
+    assert_eq!(parse_key(b"\x1B[97;1u").text, None);
+    // Text that doesn't fit in the key event is reported as text
+    assert_eq!(
+        Event::parse_from(b"\x1B[97;;128104:8205:128105:8205:128103u").unwrap(),
+        Some(Event::Text(
+            "\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}".to_string()
+        ))
+    );
+    assert_eq!(
+        Event::parse_from(b"\x1B[97;1;xu").unwrap_err().kind(),
+        ParseErrorKind::MalformedParameters
+    );
+
+    let mut buf = [0; 32];
+    let written = Event::Key(KeyEvent::new(KeyCode::Char('a')).text(KeyText::from('ä')))
+        .encode(&mut buf, Encoding::Kitty(KittyFlags::all()))
+        .unwrap();
+    assert_eq!(buf[..written], *b"\x1B[97;1;228u");
+
+    let written =
+        Event::Key(KeyEvent::new(KeyCode::Char('e')).text(KeyText::new("e\u{301}").unwrap()))
+            .encode(&mut buf, Encoding::Kitty(KittyFlags::all()))
+            .unwrap();
+    assert_eq!(buf[..written], *b"\x1B[101;1;101:769u");
+
+    // Text isn't sent for release events
+    let written = Event::Key(
+        KeyEvent::new(KeyCode::Char('a'))
+            .kind(KeyEventKind::Release)
+            .text(KeyText::from('a')),
+    )
+    .encode(&mut buf, Encoding::Kitty(KittyFlags::all()))
+    .unwrap();
+    assert_eq!(buf[..written], *b"\x1B[97;1:3u");
+
+    let written = Event::Key(KeyEvent::new(KeyCode::Char('a')).text(KeyText::from('ä')))
+        .encode(
+            &mut buf,
+            Encoding::Kitty(
+                KittyFlags::DISAMBIGUATE_ESCAPE_CODES | KittyFlags::REPORT_ALL_KEYS_AS_ESCAPE_CODES,
+            ),
+        )
+        .unwrap();
+    assert_eq!(buf[..written], *b"\x1B[97u");
 }
 
 #[test]
@@ -1567,44 +1707,38 @@ fn test_parse_from_subsequent_calls() {
     // parse_csi_rxvt_mouse
     assert_eq!(
         Event::parse_from(b"\x1B[32;30;40;M").unwrap(),
-        Some(Event::Mouse(MouseEvent {
-            kind: MouseEventKind::Down(MouseButton::Left),
-            column: 29,
-            row: 39,
-            modifiers: KeyModifiers::empty(),
-        }))
+        Some(Event::Mouse(MouseEvent::new(
+            MouseEventKind::Down(MouseButton::Left),
+            29,
+            39
+        )))
     );
 
     // parse_csi_normal_mouse
     assert_eq!(
         Event::parse_from(b"\x1B[M0\x60\x70").unwrap(),
-        Some(Event::Mouse(MouseEvent {
-            kind: MouseEventKind::Down(MouseButton::Left),
-            column: 63,
-            row: 79,
-            modifiers: KeyModifiers::CTRL,
-        }))
+        Some(Event::Mouse(
+            MouseEvent::new(MouseEventKind::Down(MouseButton::Left), 63, 79)
+                .modifiers(KeyModifiers::CTRL)
+        ))
     );
     // mouse with invalid coordinates (should return 0 and not panic)
     assert_eq!(
         Event::parse_from(b"\x1B[M0\x00\x00").unwrap(),
-        Some(Event::Mouse(MouseEvent {
-            kind: MouseEventKind::Down(MouseButton::Left),
-            column: 0,
-            row: 0,
-            modifiers: KeyModifiers::CTRL,
-        }))
+        Some(Event::Mouse(
+            MouseEvent::new(MouseEventKind::Down(MouseButton::Left), 0, 0)
+                .modifiers(KeyModifiers::CTRL)
+        ))
     );
 
     // parse_csi_sgr_mouse
     assert_eq!(
         Event::parse_from(b"\x1B[<0;20;10;M").unwrap(),
-        Some(Event::Mouse(MouseEvent {
-            kind: MouseEventKind::Down(MouseButton::Left),
-            column: 19,
-            row: 9,
-            modifiers: KeyModifiers::empty(),
-        }))
+        Some(Event::Mouse(MouseEvent::new(
+            MouseEventKind::Down(MouseButton::Left),
+            19,
+            9
+        )))
     );
 
     // parse_utf8_char
@@ -1764,12 +1898,20 @@ fn test_parse_csi_focus() {
 fn test_parse_csi_rxvt_mouse() {
     assert_eq!(
         Event::parse_from(b"\x1B[32;30;40;M").unwrap(),
-        Some(Event::Mouse(MouseEvent {
-            kind: MouseEventKind::Down(MouseButton::Left),
-            column: 29,
-            row: 39,
-            modifiers: KeyModifiers::empty(),
-        }))
+        Some(Event::Mouse(MouseEvent::new(
+            MouseEventKind::Down(MouseButton::Left),
+            29,
+            39
+        )))
+    );
+    // Coordinates are 1-based, but a 0 is clamped instead of overflowing
+    assert_eq!(
+        Event::parse_from(b"\x1B[32;0;0;M").unwrap(),
+        Some(Event::Mouse(MouseEvent::new(
+            MouseEventKind::Down(MouseButton::Left),
+            0,
+            0
+        )))
     );
 }
 
@@ -1777,12 +1919,10 @@ fn test_parse_csi_rxvt_mouse() {
 fn test_parse_csi_normal_mouse() {
     assert_eq!(
         Event::parse_from(b"\x1B[M0\x60\x70").unwrap(),
-        Some(Event::Mouse(MouseEvent {
-            kind: MouseEventKind::Down(MouseButton::Left),
-            column: 63,
-            row: 79,
-            modifiers: KeyModifiers::CTRL,
-        }))
+        Some(Event::Mouse(
+            MouseEvent::new(MouseEventKind::Down(MouseButton::Left), 63, 79)
+                .modifiers(KeyModifiers::CTRL)
+        ))
     );
 }
 
@@ -1790,137 +1930,110 @@ fn test_parse_csi_normal_mouse() {
 fn test_parse_csi_sgr_mouse() {
     assert_eq!(
         Event::parse_from(b"\x1B[<35;20;10M").unwrap(),
-        Some(Event::Mouse(MouseEvent {
-            kind: MouseEventKind::Moved,
-            column: 19,
-            row: 9,
-            modifiers: KeyModifiers::empty()
-        }))
+        Some(Event::Mouse(MouseEvent::new(MouseEventKind::Moved, 19, 9)))
     );
     let mut buf = [0; 16];
-    let written = Event::Mouse(MouseEvent {
-        kind: MouseEventKind::Moved,
-        column: 19,
-        row: 9,
-        modifiers: KeyModifiers::empty(),
-    })
-    .encode(&mut buf, Encoding::Xterm)
-    .unwrap();
+    let written = Event::Mouse(MouseEvent::new(MouseEventKind::Moved, 19, 9))
+        .encode(&mut buf, Encoding::Xterm)
+        .unwrap();
     assert_eq!(buf[..written], *b"\x1B[<35;20;10M");
     assert_eq!(
         Event::parse_from(b"\x1B[<39;20;10M").unwrap(),
-        Some(Event::Mouse(MouseEvent {
-            kind: MouseEventKind::Moved,
-            column: 19,
-            row: 9,
-            modifiers: KeyModifiers::SHIFT
-        }))
+        Some(Event::Mouse(
+            MouseEvent::new(MouseEventKind::Moved, 19, 9).modifiers(KeyModifiers::SHIFT)
+        ))
     );
     let mut buf = [0; 16];
-    let written = Event::Mouse(MouseEvent {
-        kind: MouseEventKind::Moved,
-        column: 19,
-        row: 9,
-        modifiers: KeyModifiers::SHIFT,
-    })
-    .encode(&mut buf, Encoding::Xterm)
-    .unwrap();
+    let written =
+        Event::Mouse(MouseEvent::new(MouseEventKind::Moved, 19, 9).modifiers(KeyModifiers::SHIFT))
+            .encode(&mut buf, Encoding::Xterm)
+            .unwrap();
     assert_eq!(buf[..written], *b"\x1B[<39;20;10M");
 
     assert_eq!(
         Event::parse_from(b"\x1B[<0;20;10;M").unwrap(),
-        Some(Event::Mouse(MouseEvent {
-            kind: MouseEventKind::Down(MouseButton::Left),
-            column: 19,
-            row: 9,
-            modifiers: KeyModifiers::empty(),
-        }))
+        Some(Event::Mouse(MouseEvent::new(
+            MouseEventKind::Down(MouseButton::Left),
+            19,
+            9
+        )))
     );
     assert_eq!(
         Event::parse_from(b"\x1B[<0;20;10M").unwrap(),
-        Some(Event::Mouse(MouseEvent {
-            kind: MouseEventKind::Down(MouseButton::Left),
-            column: 19,
-            row: 9,
-            modifiers: KeyModifiers::empty(),
-        }))
+        Some(Event::Mouse(MouseEvent::new(
+            MouseEventKind::Down(MouseButton::Left),
+            19,
+            9
+        )))
     );
     let mut buf = [0; 16];
-    let written = Event::Mouse(MouseEvent {
-        kind: MouseEventKind::Down(MouseButton::Left),
-        column: 19,
-        row: 9,
-        modifiers: KeyModifiers::empty(),
-    })
+    let written = Event::Mouse(MouseEvent::new(
+        MouseEventKind::Down(MouseButton::Left),
+        19,
+        9,
+    ))
     .encode(&mut buf, Encoding::Xterm)
     .unwrap();
     assert_eq!(buf[..written], *b"\x1B[<0;20;10M");
 
     assert_eq!(
         Event::parse_from(b"\x1B[<0;20;10;m").unwrap(),
-        Some(Event::Mouse(MouseEvent {
-            kind: MouseEventKind::Up(MouseButton::Left),
-            column: 19,
-            row: 9,
-            modifiers: KeyModifiers::empty(),
-        }))
+        Some(Event::Mouse(MouseEvent::new(
+            MouseEventKind::Up(MouseButton::Left),
+            19,
+            9
+        )))
     );
     assert_eq!(
         Event::parse_from(b"\x1B[<0;20;10m").unwrap(),
-        Some(Event::Mouse(MouseEvent {
-            kind: MouseEventKind::Up(MouseButton::Left),
-            column: 19,
-            row: 9,
-            modifiers: KeyModifiers::empty(),
-        }))
+        Some(Event::Mouse(MouseEvent::new(
+            MouseEventKind::Up(MouseButton::Left),
+            19,
+            9
+        )))
     );
     let mut buf = [0; 16];
-    let written = Event::Mouse(MouseEvent {
-        kind: MouseEventKind::Up(MouseButton::Left),
-        column: 19,
-        row: 9,
-        modifiers: KeyModifiers::empty(),
-    })
+    let written = Event::Mouse(MouseEvent::new(
+        MouseEventKind::Up(MouseButton::Left),
+        19,
+        9,
+    ))
     .encode(&mut buf, Encoding::Xterm)
     .unwrap();
     assert_eq!(buf[..written], *b"\x1B[<0;20;10m");
 
     assert_eq!(
         Event::parse_from(b"\x1B[<2;20;10M").unwrap(),
-        Some(Event::Mouse(MouseEvent {
-            kind: MouseEventKind::Down(MouseButton::Right),
-            column: 19,
-            row: 9,
-            modifiers: KeyModifiers::empty(),
-        }))
+        Some(Event::Mouse(MouseEvent::new(
+            MouseEventKind::Down(MouseButton::Right),
+            19,
+            9
+        )))
     );
     let mut buf = [0; 16];
-    let written = Event::Mouse(MouseEvent {
-        kind: MouseEventKind::Down(MouseButton::Right),
-        column: 19,
-        row: 9,
-        modifiers: KeyModifiers::empty(),
-    })
+    let written = Event::Mouse(MouseEvent::new(
+        MouseEventKind::Down(MouseButton::Right),
+        19,
+        9,
+    ))
     .encode(&mut buf, Encoding::Xterm)
     .unwrap();
     assert_eq!(buf[..written], *b"\x1B[<2;20;10M");
 
     assert_eq!(
         Event::parse_from(b"\x1B[<1;20;10M").unwrap(),
-        Some(Event::Mouse(MouseEvent {
-            kind: MouseEventKind::Down(MouseButton::Middle),
-            column: 19,
-            row: 9,
-            modifiers: KeyModifiers::empty(),
-        }))
+        Some(Event::Mouse(MouseEvent::new(
+            MouseEventKind::Down(MouseButton::Middle),
+            19,
+            9
+        )))
     );
     let mut buf = [0; 16];
-    let written = Event::Mouse(MouseEvent {
-        kind: MouseEventKind::Down(MouseButton::Middle),
-        column: 19,
-        row: 9,
-        modifiers: KeyModifiers::empty(),
-    })
+    let written = Event::Mouse(MouseEvent::new(
+        MouseEventKind::Down(MouseButton::Middle),
+        19,
+        9,
+    ))
     .encode(&mut buf, Encoding::Xterm)
     .unwrap();
     assert_eq!(buf[..written], *b"\x1B[<1;20;10M");
@@ -2829,3 +2942,1085 @@ fn test_parse_csi_numbered_escape_code_with_types() {
         .unwrap();
     assert_eq!(buf[..written], *b"\x1B[6~");
 }
+
+#[test]
+fn test_parse_errors() {
+    let err = Event::parse_from(&[0xE2, 0x82, 0x28]).unwrap_err();
+    assert_eq!(err.kind(), ParseErrorKind::InvalidUtf8);
+    assert_eq!(err.bytes(), [0xE2, 0x82, 0x28]);
+    assert_eq!(err.offset(), 2);
+
+    let err = Event::parse_from(b"\x1B[1;5X").unwrap_err();
+    assert_eq!(err.kind(), ParseErrorKind::UnknownCsiFinal(b'X'));
+    assert_eq!(err.bytes(), b"\x1B[1;5X");
+    assert_eq!(err.offset(), 5);
+
+    let err = Event::parse_from(b"\x1BOZ").unwrap_err();
+    assert_eq!(err.kind(), ParseErrorKind::UnknownSs3Final(b'Z'));
+    assert_eq!(err.offset(), 2);
+
+    let err = Event::parse_from(b"\x1B[99;x~").unwrap_err();
+    assert_eq!(err.kind(), ParseErrorKind::UnknownKeyCode);
+
+    let err = Event::parse_from(b"\x1B[<x;1;1M").unwrap_err();
+    assert_eq!(err.kind(), ParseErrorKind::MalformedParameters);
+
+    let err = Event::parse_from(b"\x1B[<192;1;1M").unwrap_err();
+    assert_eq!(err.kind(), ParseErrorKind::UnsupportedMouseButton);
+
+    let err = Event::parse_from(b"\x1B[?1X").unwrap_err();
+    assert_eq!(err.kind(), ParseErrorKind::UnknownCsiFinal(b'X'));
+    assert_eq!(err.offset(), 4);
+
+    let err = Event::parse_from(b"\x1B\x1Bx").unwrap_err();
+    assert_eq!(err.kind(), ParseErrorKind::UnknownSequence);
+}
+
+#[test]
+fn test_cursor_position_reply() {
+    let event = Event::Reply(TerminalReply::CursorPosition { row: 19, column: 9 });
+    assert_eq!(
+        Event::parse_from(b"\x1B[20;10R").unwrap(),
+        Some(event.clone())
+    );
+
+    assert_eq!(
+        Event::parse_from(b"\x1B[1;1R").unwrap(),
+        Some(Event::Reply(TerminalReply::CursorPosition {
+            row: 0,
+            column: 0
+        }))
+    );
+    assert_eq!(
+        Event::parse_from(b"\x1B[1;80R").unwrap(),
+        Some(Event::Reply(TerminalReply::CursorPosition {
+            row: 0,
+            column: 79
+        }))
+    );
+    // Ambiguous with Shift+F3
+    assert_eq!(
+        Event::parse_from(b"\x1B[1;2R").unwrap(),
+        Some(Event::Key(
+            KeyEvent::new(KeyCode::F(3)).modifiers(KeyModifiers::SHIFT)
+        ))
+    );
+    // The ambiguous range is parsed as a reply when one is expected
+    let options = ParserOptions::new().cursor_position_reports(true);
+    for column in 2..=16 {
+        let sequence = format!("\x1B[1;{column}R");
+        assert!(
+            Event::parse_from(sequence.as_bytes())
+                .unwrap()
+                .unwrap()
+                .is_key()
+        );
+        assert_eq!(
+            Event::parse_with_options(sequence.as_bytes(), options).unwrap(),
+            Some(Event::Reply(TerminalReply::CursorPosition {
+                row: 0,
+                column: column - 1
+            }))
+        );
+    }
+    // Modified F3 with an event type is still a key
+    assert_eq!(
+        Event::parse_with_options(b"\x1B[1;5:1R", options).unwrap(),
+        Some(Event::Key(
+            KeyEvent::new(KeyCode::F(3)).modifiers(KeyModifiers::CTRL)
+        ))
+    );
+
+    // Replies are only sent by the terminal, so they can't be converted to input
+    let mut buf = [0; 16];
+    assert!(event.encode(&mut buf, Encoding::Xterm).is_err());
+}
+
+#[test]
+fn test_primary_device_attributes_reply() {
+    let event = Event::Reply(TerminalReply::PrimaryDeviceAttributes(vec![62, 22, 52]));
+    assert_eq!(
+        Event::parse_from(b"\x1B[?62;22;52c").unwrap(),
+        Some(event.clone())
+    );
+
+    assert_eq!(
+        Event::parse_from(b"\x1B[?1;2c").unwrap(),
+        Some(Event::Reply(TerminalReply::PrimaryDeviceAttributes(vec![
+            1, 2
+        ])))
+    );
+    assert_eq!(
+        Event::parse_from(b"\x1B[?1;xc").unwrap_err().kind(),
+        ParseErrorKind::MalformedParameters
+    );
+}
+
+#[test]
+fn test_kitty_keyboard_flags_reply() {
+    let event = Event::Reply(TerminalReply::KittyKeyboardFlags(
+        KittyFlags::DISAMBIGUATE_ESCAPE_CODES | KittyFlags::REPORT_ALL_KEYS_AS_ESCAPE_CODES,
+    ));
+    assert_eq!(Event::parse_from(b"\x1B[?9u").unwrap(), Some(event.clone()));
+
+    assert_eq!(
+        Event::parse_from(b"\x1B[?0u").unwrap(),
+        Some(Event::Reply(TerminalReply::KittyKeyboardFlags(
+            KittyFlags::empty()
+        )))
+    );
+    assert_eq!(
+        Event::parse_from(b"\x1B[?31u").unwrap(),
+        Some(Event::Reply(TerminalReply::KittyKeyboardFlags(
+            KittyFlags::all()
+        )))
+    );
+}
+
+#[test]
+fn test_clipboard_reply() {
+    let event = Event::Reply(TerminalReply::Clipboard {
+        selection: ClipboardSelection::Clipboard,
+        data: b"hello".to_vec(),
+    });
+    assert_eq!(
+        Event::parse_from(b"\x1B]52;c;aGVsbG8=\x07").unwrap(),
+        Some(event.clone())
+    );
+    assert_eq!(
+        Event::parse_from(b"\x1B]52;c;aGVsbG8=\x1B\\").unwrap(),
+        Some(event.clone())
+    );
+
+    let event = Event::Reply(TerminalReply::Clipboard {
+        selection: ClipboardSelection::Primary,
+        data: "héllo!".as_bytes().to_vec(),
+    });
+    assert_eq!(
+        Event::parse_from(b"\x1B]52;p;aMOpbGxvIQ==\x07").unwrap(),
+        Some(event.clone())
+    );
+
+    assert_eq!(
+        Event::parse_from(b"\x1B]52;;\x07").unwrap(),
+        Some(Event::Reply(TerminalReply::Clipboard {
+            selection: ClipboardSelection::Select,
+            data: Vec::new(),
+        }))
+    );
+    assert_eq!(
+        Event::parse_from(b"\x1B]52;3;YQ\x07").unwrap(),
+        Some(Event::Reply(TerminalReply::Clipboard {
+            selection: ClipboardSelection::CutBuffer(3),
+            data: b"a".to_vec(),
+        }))
+    );
+
+    // Incomplete sequences
+    assert_eq!(Event::parse_from(b"\x1B]52;c;aGVs").unwrap(), None);
+    assert_eq!(Event::parse_from(b"\x1B]52;c;aGVsbG8=\x1B").unwrap(), None);
+
+    assert_eq!(
+        Event::parse_from(b"\x1B]52;c;?\x07").unwrap_err().kind(),
+        ParseErrorKind::MalformedParameters
+    );
+    assert_eq!(
+        Event::parse_from(b"\x1B]52;c;aGVs\x1B[A")
+            .unwrap_err()
+            .kind(),
+        ParseErrorKind::UnknownSequence
+    );
+}
+
+#[test]
+fn test_alt_right_bracket() {
+    assert_eq!(
+        Event::parse_from(b"\x1B]").unwrap(),
+        Some(Event::Key(
+            KeyEvent::new(KeyCode::Char(']')).modifiers(KeyModifiers::ALT)
+        )),
+    );
+}
+
+#[test]
+fn test_color_reply() {
+    let event = Event::Reply(TerminalReply::Color {
+        target: ColorTarget::Background,
+        color: RgbColor::new(0x1e1e, 0x1f1f, 0x2828),
+    });
+    assert_eq!(
+        Event::parse_from(b"\x1B]11;rgb:1e1e/1f1f/2828\x07").unwrap(),
+        Some(event.clone())
+    );
+    assert_eq!(
+        Event::parse_from(b"\x1B]11;rgb:1e1e/1f1f/2828\x1B\\").unwrap(),
+        Some(event.clone())
+    );
+    // Shorter components are scaled to 16 bits
+    assert_eq!(
+        Event::parse_from(b"\x1B]11;rgb:1e/1f/28\x1B\\").unwrap(),
+        Some(event.clone())
+    );
+
+    assert_eq!(
+        Event::parse_from(b"\x1B]10;rgb:f/0/8\x07").unwrap(),
+        Some(Event::Reply(TerminalReply::Color {
+            target: ColorTarget::Foreground,
+            color: RgbColor::new(0xffff, 0, 0x8888),
+        }))
+    );
+    assert_eq!(
+        Event::parse_from(b"\x1B]12;rgb:ffff/ffff/ffff\x07").unwrap(),
+        Some(Event::Reply(TerminalReply::Color {
+            target: ColorTarget::Cursor,
+            color: RgbColor::from_rgb8(255, 255, 255),
+        }))
+    );
+
+    let event = Event::Reply(TerminalReply::Color {
+        target: ColorTarget::Palette(1),
+        color: RgbColor::new(0xcdcd, 0, 0),
+    });
+    assert_eq!(
+        Event::parse_from(b"\x1B]4;1;rgb:cdcd/0000/0000\x1B\\").unwrap(),
+        Some(event.clone())
+    );
+
+    // Incomplete sequences
+    assert_eq!(Event::parse_from(b"\x1B]11;rgb:1e1e/1f").unwrap(), None);
+    assert_eq!(
+        Event::parse_from(b"\x1B]11;rgb:1e1e/1f1f/2828\x1B").unwrap(),
+        None
+    );
+
+    assert_eq!(
+        Event::parse_from(b"\x1B]11;rgb:1e1e/1f1f\x07")
+            .unwrap_err()
+            .kind(),
+        ParseErrorKind::MalformedParameters
+    );
+    assert_eq!(
+        Event::parse_from(b"\x1B]11;rgb:12345/0/0\x07")
+            .unwrap_err()
+            .kind(),
+        ParseErrorKind::MalformedParameters
+    );
+    assert_eq!(
+        Event::parse_from(b"\x1B]4;256;rgb:0/0/0\x07")
+            .unwrap_err()
+            .kind(),
+        ParseErrorKind::MalformedParameters
+    );
+}
+
+#[test]
+fn test_mode_report() {
+    let event = Event::Reply(TerminalReply::ModeReport {
+        mode: 2026,
+        private: true,
+        state: ModeState::Reset,
+    });
+    assert_eq!(
+        Event::parse_from(b"\x1B[?2026;2$y").unwrap(),
+        Some(event.clone())
+    );
+
+    let event = Event::Reply(TerminalReply::ModeReport {
+        mode: 4,
+        private: false,
+        state: ModeState::PermanentlyReset,
+    });
+    assert_eq!(
+        Event::parse_from(b"\x1B[4;4$y").unwrap(),
+        Some(event.clone())
+    );
+
+    assert_eq!(
+        Event::parse_from(b"\x1B[?1016;0$y").unwrap(),
+        Some(Event::Reply(TerminalReply::ModeReport {
+            mode: 1016,
+            private: true,
+            state: ModeState::NotRecognized,
+        }))
+    );
+    assert_eq!(Event::parse_from(b"\x1B[?2026;1$").unwrap(), None);
+    assert_eq!(
+        Event::parse_from(b"\x1B[?2026;5$y").unwrap_err().kind(),
+        ParseErrorKind::MalformedParameters
+    );
+    assert_eq!(
+        Event::parse_from(b"\x1B[?2026;1y").unwrap_err().kind(),
+        ParseErrorKind::UnknownCsiFinal(b'y')
+    );
+}
+
+#[test]
+fn test_terminal_version_reply() {
+    let event = Event::Reply(TerminalReply::TerminalVersion("XTerm(390)".to_string()));
+    assert_eq!(
+        Event::parse_from(b"\x1BP>|XTerm(390)\x1B\\").unwrap(),
+        Some(event.clone())
+    );
+
+    assert_eq!(Event::parse_from(b"\x1BP>|kitty(0.3").unwrap(), None);
+    assert_eq!(Event::parse_from(b"\x1BP>|kitty\x1B").unwrap(), None);
+    assert_eq!(
+        Event::parse_from(b"\x1BP>|kitty\x1B[A").unwrap_err().kind(),
+        ParseErrorKind::UnknownSequence
+    );
+    assert_eq!(
+        Event::parse_from(b"\x1BPz\x1B\\").unwrap_err().kind(),
+        ParseErrorKind::UnknownSequence
+    );
+}
+
+#[test]
+fn test_capabilities_reply() {
+    let event = Event::Reply(TerminalReply::Capabilities {
+        valid: true,
+        capabilities: vec![
+            ("TN".to_string(), Some("xterm".to_string())),
+            ("RGB".to_string(), None),
+        ],
+    });
+    assert_eq!(
+        Event::parse_from(b"\x1BP1+r544E=787465726D;524742\x1B\\").unwrap(),
+        Some(event.clone())
+    );
+    assert_eq!(
+        Event::parse_from(b"\x1BP1+r544e=787465726d;524742\x1B\\").unwrap(),
+        Some(event.clone())
+    );
+
+    let event = Event::Reply(TerminalReply::Capabilities {
+        valid: false,
+        capabilities: vec![("Smulx".to_string(), None)],
+    });
+    assert_eq!(
+        Event::parse_from(b"\x1BP0+r536D756C78\x1B\\").unwrap(),
+        Some(event.clone())
+    );
+
+    assert_eq!(
+        Event::parse_from(b"\x1BP0+r\x1B\\").unwrap(),
+        Some(Event::Reply(TerminalReply::Capabilities {
+            valid: false,
+            capabilities: vec![],
+        }))
+    );
+    assert_eq!(
+        Event::parse_from(b"\x1BP1+r544=78\x1B\\")
+            .unwrap_err()
+            .kind(),
+        ParseErrorKind::MalformedParameters
+    );
+}
+
+#[test]
+fn test_alt_shift_p() {
+    assert_eq!(
+        Event::parse_from(b"\x1BP").unwrap(),
+        Some(Event::Key(
+            KeyEvent::new(KeyCode::Char('P')).modifiers(KeyModifiers::ALT | KeyModifiers::SHIFT)
+        ))
+    );
+}
+
+#[test]
+fn test_resize() {
+    let event = Event::Resize { rows: 24, cols: 80 };
+    assert_eq!(
+        Event::parse_from(b"\x1B[48;24;80;480;800t").unwrap(),
+        Some(event.clone())
+    );
+    assert_eq!(
+        Event::parse_from(b"\x1B[8;24;80t").unwrap(),
+        Some(event.clone())
+    );
+    let mut buf = [0; 32];
+    let written = event.encode(&mut buf, Encoding::Xterm).unwrap();
+    assert_eq!(buf[..written], *b"\x1B[48;24;80;0;0t");
+    assert_eq!(Event::parse_from(&buf[..written]).unwrap(), Some(event));
+
+    assert_eq!(Event::parse_from(b"\x1B[48;24;80;480;").unwrap(), None);
+    assert_eq!(
+        Event::parse_from(b"\x1B[48;24t").unwrap_err().kind(),
+        ParseErrorKind::MalformedParameters
+    );
+    assert_eq!(
+        Event::parse_from(b"\x1B[4;480;800t").unwrap_err().kind(),
+        ParseErrorKind::UnknownSequence
+    );
+}
+
+#[test]
+fn test_modify_other_keys() {
+    let ctrl_i = Event::Key(KeyEvent::new(KeyCode::Char('i')).modifiers(KeyModifiers::CTRL));
+    assert_eq!(
+        Event::parse_from(b"\x1B[27;5;105~").unwrap(),
+        Some(ctrl_i.clone())
+    );
+    let mut buf = [0; 16];
+    let written = ctrl_i
+        .encode(&mut buf, Encoding::ModifyOtherKeys { level: 2 })
+        .unwrap();
+    assert_eq!(buf[..written], *b"\x1B[27;5;105~");
+    // Level 1 keeps the legacy encoding for keys it can represent
+    let written = ctrl_i
+        .encode(&mut buf, Encoding::ModifyOtherKeys { level: 1 })
+        .unwrap();
+    assert_eq!(buf[..written], *b"\x09");
+
+    let ctrl_enter = Event::Key(KeyEvent::new(KeyCode::Enter).modifiers(KeyModifiers::CTRL));
+    assert_eq!(
+        Event::parse_from(b"\x1B[27;5;13~").unwrap(),
+        Some(ctrl_enter.clone())
+    );
+    let written = ctrl_enter
+        .encode(&mut buf, Encoding::ModifyOtherKeys { level: 1 })
+        .unwrap();
+    assert_eq!(buf[..written], *b"\x1B[27;5;13~");
+
+    let ctrl_shift_a = Event::Key(
+        KeyEvent::new(KeyCode::Char('a')).modifiers(KeyModifiers::CTRL | KeyModifiers::SHIFT),
+    );
+    assert_eq!(
+        Event::parse_from(b"\x1B[27;6;65~").unwrap(),
+        Some(ctrl_shift_a.clone())
+    );
+    let written = ctrl_shift_a
+        .encode(&mut buf, Encoding::ModifyOtherKeys { level: 1 })
+        .unwrap();
+    assert_eq!(buf[..written], *b"\x1B[27;6;65~");
+
+    let alt_tab = Event::Key(KeyEvent::new(KeyCode::Tab).modifiers(KeyModifiers::ALT));
+    assert_eq!(
+        Event::parse_from(b"\x1B[27;3;9~").unwrap(),
+        Some(alt_tab.clone())
+    );
+    let written = alt_tab
+        .encode(&mut buf, Encoding::ModifyOtherKeys { level: 2 })
+        .unwrap();
+    assert_eq!(buf[..written], *b"\x1B[27;3;9~");
+    let written = alt_tab
+        .encode(&mut buf, Encoding::ModifyOtherKeys { level: 1 })
+        .unwrap();
+    assert_eq!(buf[..written], *b"\x1B\t");
+
+    // Unmodified and shifted text is sent as-is
+    let written = Event::Key(KeyEvent::new(KeyCode::Char('A')))
+        .encode(&mut buf, Encoding::ModifyOtherKeys { level: 2 })
+        .unwrap();
+    assert_eq!(buf[..written], *b"A");
+
+    assert_eq!(
+        Event::parse_from(b"\x1B[27;5;8~").unwrap(),
+        Some(Event::Key(
+            KeyEvent::new(KeyCode::Backspace).modifiers(KeyModifiers::CTRL)
+        ))
+    );
+    assert_eq!(Event::parse_from(b"\x1B[27;5;10").unwrap(), None);
+    assert_eq!(
+        Event::parse_from(b"\x1B[27;5~").unwrap_err().kind(),
+        ParseErrorKind::MalformedParameters
+    );
+}
+
+#[test]
+fn test_format_other_keys() {
+    // formatOtherKeys=1 uses the CSI u form with the shifted character
+    assert_eq!(
+        Event::parse_from(b"\x1B[65;6u").unwrap(),
+        Some(Event::Key(
+            KeyEvent::new(KeyCode::Char('a')).modifiers(KeyModifiers::CTRL | KeyModifiers::SHIFT)
+        ))
+    );
+    assert_eq!(
+        Event::parse_from(b"\x1B[33;6u").unwrap(),
+        Some(Event::Key(
+            KeyEvent::new(KeyCode::Char('!')).modifiers(KeyModifiers::CTRL | KeyModifiers::SHIFT)
+        ))
+    );
+    assert_eq!(
+        Event::parse_from(b"\x1B[13;5u").unwrap(),
+        Some(Event::Key(
+            KeyEvent::new(KeyCode::Enter).modifiers(KeyModifiers::CTRL)
+        ))
+    );
+    assert_eq!(
+        Event::parse_from(b"\x1B[8;5u").unwrap(),
+        Some(Event::Key(
+            KeyEvent::new(KeyCode::Backspace).modifiers(KeyModifiers::CTRL)
+        ))
+    );
+}
+
+#[test]
+fn test_sgr_pixels_mouse() {
+    let options = ParserOptions::new().sgr_pixels(true);
+    let event = Event::Mouse(
+        MouseEvent::new(MouseEventKind::Down(MouseButton::Left), 1253, 381)
+            .modifiers(KeyModifiers::CTRL)
+            .unit(PositionUnit::Pixels),
+    );
+    assert_eq!(
+        Event::parse_with_options(b"\x1B[<16;1254;382M", options).unwrap(),
+        Some(event.clone())
+    );
+    let mut buf = [0; 32];
+    let written = event.encode(&mut buf, Encoding::Xterm).unwrap();
+    assert_eq!(buf[..written], *b"\x1B[<16;1254;382M");
+
+    assert_eq!(
+        Event::parse_with_options(b"\x1B[<0;1254;382m", options).unwrap(),
+        Some(Event::Mouse(
+            MouseEvent::new(MouseEventKind::Up(MouseButton::Left), 1253, 381)
+                .unit(PositionUnit::Pixels)
+        ))
+    );
+    // Cells are used unless pixel mode is enabled
+    assert_eq!(
+        Event::parse_from(b"\x1B[<0;1254;382M").unwrap(),
+        Some(Event::Mouse(MouseEvent::new(
+            MouseEventKind::Down(MouseButton::Left),
+            1253,
+            381
+        )))
+    );
+    // Coordinates are 1-based, but a 0 is clamped instead of overflowing
+    assert_eq!(
+        Event::parse_with_options(b"\x1B[<0;0;0M", options).unwrap(),
+        Some(Event::Mouse(
+            MouseEvent::new(MouseEventKind::Down(MouseButton::Left), 0, 0)
+                .unit(PositionUnit::Pixels)
+        ))
+    );
+    assert_eq!(
+        Event::parse_from(b"\x1B[<0;0;0M").unwrap(),
+        Some(Event::Mouse(MouseEvent::new(
+            MouseEventKind::Down(MouseButton::Left),
+            0,
+            0
+        )))
+    );
+}
+
+#[test]
+fn test_utf8_mouse() {
+    let options = ParserOptions::new().utf8_mouse(true);
+    // Column 300 and row 40 are sent as U+014D and 'I'
+    let sequence = "\x1B[M ōI".as_bytes();
+    assert_eq!(
+        Event::parse_with_options(sequence, options).unwrap(),
+        Some(Event::Mouse(MouseEvent::new(
+            MouseEventKind::Down(MouseButton::Left),
+            300,
+            40
+        )))
+    );
+    assert_eq!(
+        Event::parse_with_options(&sequence[..5], options).unwrap(),
+        None
+    );
+    // Values below 128 are the same as the normal encoding
+    assert_eq!(
+        Event::parse_with_options(b"\x1B[M0\x60\x70", options).unwrap(),
+        Some(Event::Mouse(
+            MouseEvent::new(MouseEventKind::Down(MouseButton::Left), 63, 79)
+                .modifiers(KeyModifiers::CTRL)
+        ))
+    );
+    assert_eq!(
+        Event::parse_with_options(b"\x1B[M \xFFI", options)
+            .unwrap_err()
+            .kind(),
+        ParseErrorKind::InvalidUtf8
+    );
+}
+
+#[test]
+fn test_eight_bit_controls() {
+    let options = ParserOptions::new().eight_bit_controls(true);
+    assert_eq!(
+        Event::parse_with_options(b"\x9BA", options).unwrap(),
+        Some(Event::Key(KeyCode::Up.into()))
+    );
+    assert_eq!(
+        Event::parse_with_options(b"\x9B1;5D", options).unwrap(),
+        Some(Event::Key(
+            KeyEvent::new(KeyCode::Left).modifiers(KeyModifiers::CTRL)
+        ))
+    );
+    assert_eq!(
+        Event::parse_with_options(b"\x8FP", options).unwrap(),
+        Some(Event::Key(KeyCode::F(1).into()))
+    );
+    assert_eq!(Event::parse_with_options(b"\x9B1;", options).unwrap(), None);
+
+    let err = Event::parse_with_options(b"\x8FZ", options).unwrap_err();
+    assert_eq!(err.kind(), ParseErrorKind::UnknownSs3Final(b'Z'));
+    assert_eq!(err.bytes(), b"\x8FZ");
+    assert_eq!(err.offset(), 1);
+
+    // 8-bit controls aren't recognized by default
+    assert_eq!(
+        Event::parse_from(b"\x9BA").unwrap_err().kind(),
+        ParseErrorKind::InvalidUtf8
+    );
+}
+
+#[test]
+fn test_extra_mouse_buttons() {
+    let mut buf = [0; 16];
+    for (sequence, event) in [
+        (
+            b"\x1B[<128;10;5M".as_slice(),
+            MouseEvent::new(MouseEventKind::Down(MouseButton::Back), 9, 4),
+        ),
+        (
+            b"\x1B[<129;10;5m",
+            MouseEvent::new(MouseEventKind::Up(MouseButton::Forward), 9, 4),
+        ),
+        (
+            b"\x1B[<160;10;5M",
+            MouseEvent::new(MouseEventKind::Drag(MouseButton::Back), 9, 4),
+        ),
+        (
+            b"\x1B[<131;10;5M",
+            MouseEvent::new(MouseEventKind::Down(MouseButton::Other(11)), 9, 4),
+        ),
+        (
+            b"\x1B[<146;10;5M",
+            MouseEvent::new(MouseEventKind::Down(MouseButton::Other(10)), 9, 4)
+                .modifiers(KeyModifiers::CTRL),
+        ),
+    ] {
+        assert_eq!(
+            Event::parse_from(sequence).unwrap(),
+            Some(Event::Mouse(event))
+        );
+        let written = Event::Mouse(event)
+            .encode(&mut buf, Encoding::Xterm)
+            .unwrap();
+        assert_eq!(buf[..written], *sequence);
+    }
+
+    // urxvt encoding
+    assert_eq!(
+        Event::parse_from(b"\x1B[161;10;5M").unwrap(),
+        Some(Event::Mouse(MouseEvent::new(
+            MouseEventKind::Down(MouseButton::Forward),
+            9,
+            4
+        )))
+    );
+
+    // The X11 numbers for the back and forward buttons are encoded as those buttons
+    for (button, sequence) in [
+        (MouseButton::Other(8), b"\x1B[<128;1;1M"),
+        (MouseButton::Other(9), b"\x1B[<129;1;1M"),
+    ] {
+        let written = Event::Mouse(MouseEvent::new(MouseEventKind::Down(button), 0, 0))
+            .encode(&mut buf, Encoding::Xterm)
+            .unwrap();
+        assert_eq!(buf[..written], *sequence);
+    }
+
+    for button in [0, 1, 7, 12, 255] {
+        let error = Event::Mouse(MouseEvent::new(
+            MouseEventKind::Down(MouseButton::Other(button)),
+            0,
+            0,
+        ))
+        .encode(&mut buf, Encoding::Xterm)
+        .unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::Unsupported);
+    }
+}
+
+#[test]
+fn test_scroll_amount() {
+    let mut buf = [0; 16];
+    let event = MouseEvent::new(MouseEventKind::Scroll(ScrollDirection::Down), 9, 4)
+        .modifiers(KeyModifiers::CTRL);
+    let precise = event.scroll_amount(ScrollAmount::new(0.0, -2.5, ScrollUnit::Pixels));
+    assert_ne!(event, precise);
+    assert_eq!(
+        precise.scroll_amount,
+        Some(ScrollAmount::new(0.0, -2.5, ScrollUnit::Pixels))
+    );
+
+    // Terminals can only report the direction
+    let written = Event::Mouse(precise)
+        .encode(&mut buf, Encoding::Xterm)
+        .unwrap();
+    assert_eq!(buf[..written], *b"\x1B[<81;10;5M");
+    assert_eq!(
+        Event::parse_from(&buf[..written]).unwrap(),
+        Some(Event::Mouse(event))
+    );
+}
+
+#[test]
+fn test_ime_not_encodable() {
+    let mut buf = [0; 16];
+    for ime_event in [
+        ImeEvent::Enabled,
+        ImeEvent::preedit("にほ"),
+        ImeEvent::Commit("日本".to_string()),
+        ImeEvent::Disabled,
+    ] {
+        let event = Event::Ime(ime_event);
+        assert!(event.is_ime());
+        assert_eq!(
+            event.encode(&mut buf, Encoding::Xterm).unwrap_err().kind(),
+            std::io::ErrorKind::Unsupported
+        );
+    }
+}
+
+#[test]
+fn test_text_event() {
+    let mut buf = [0; 32];
+    let event = Event::Text("👩‍💻é".to_string());
+    assert_eq!(event.as_text(), Some("👩‍💻é"));
+    let written = event.encode(&mut buf, Encoding::Xterm).unwrap();
+    assert_eq!(buf[..written], *"👩‍💻é".as_bytes());
+}
+
+#[test]
+fn test_legacy_control_options() {
+    let ctrl = |c| {
+        Some(Event::Key(
+            KeyEvent::new(KeyCode::Char(c)).modifiers(KeyModifiers::CTRL),
+        ))
+    };
+    let key = |code: KeyCode| Some(Event::Key(code.into()));
+
+    let default = ParserOptions::new();
+    for (sequence, event) in [
+        (b"\x08", ctrl('h')),
+        (b"\x7F", key(KeyCode::Backspace)),
+        (b"\n", ctrl('j')),
+        (b"\t", key(KeyCode::Tab)),
+        (b"\x1C", ctrl('4')),
+        (b"\x1F", ctrl('7')),
+    ] {
+        assert_eq!(Event::parse_with_options(sequence, default).unwrap(), event);
+    }
+
+    let options = ParserOptions::new()
+        .erase_char(0x08)
+        .newline_as_enter(true)
+        .tab_as_ctrl_i(true)
+        .ascii_control_punctuation(true);
+    for (sequence, event) in [
+        (b"\x08", key(KeyCode::Backspace)),
+        (b"\x7F", key(KeyCode::Backspace)),
+        (b"\n", key(KeyCode::Enter)),
+        (b"\t", ctrl('i')),
+        (b"\x1C", ctrl('\\')),
+        (b"\x1D", ctrl(']')),
+        (b"\x1E", ctrl('^')),
+        (b"\x1F", ctrl('_')),
+    ] {
+        assert_eq!(Event::parse_with_options(sequence, options).unwrap(), event);
+    }
+    assert!(!ParserOptions::new().erase_char(0x7F).ctrl_h_as_backspace);
+    assert!(!ParserOptions::new().erase_char(0x15).ctrl_h_as_backspace);
+
+    // Other termios control characters keep their Ctrl key mapping
+    let options = ParserOptions::new().erase_char(0x08);
+    for (sequence, event) in [
+        (b"\x03", ctrl('c')),
+        (b"\x04", ctrl('d')),
+        (b"\x15", ctrl('u')),
+        (b"\x1A", ctrl('z')),
+        (b"\x1C", ctrl('4')),
+    ] {
+        assert_eq!(Event::parse_with_options(sequence, options).unwrap(), event);
+    }
+}
+
+#[test]
+fn test_eight_bit_meta() {
+    let options = ParserOptions::new().eight_bit_meta(true);
+    let encoding = Encoding::XtermWith(XtermOptions::new().eight_bit_meta(true));
+    let mut buf = [0; 16];
+    for (sequence, event) in [
+        (
+            b"\xE1".as_slice(),
+            KeyEvent::new(KeyCode::Char('a')).modifiers(KeyModifiers::ALT),
+        ),
+        (
+            b"\xC1",
+            KeyEvent::new(KeyCode::Char('A')).modifiers(KeyModifiers::ALT | KeyModifiers::SHIFT),
+        ),
+        (
+            b"\x81",
+            KeyEvent::new(KeyCode::Char('a')).modifiers(KeyModifiers::ALT | KeyModifiers::CTRL),
+        ),
+        (
+            b"\xFF",
+            KeyEvent::new(KeyCode::Backspace).modifiers(KeyModifiers::ALT),
+        ),
+        (
+            b"\x9B",
+            KeyEvent::new(KeyCode::Esc).modifiers(KeyModifiers::ALT),
+        ),
+        // Keys that are sent as escape sequences still use the modifier parameter
+        (
+            b"\x1B[1;3A",
+            KeyEvent::new(KeyCode::Up).modifiers(KeyModifiers::ALT),
+        ),
+    ] {
+        assert_eq!(
+            Event::parse_with_options(sequence, options).unwrap(),
+            Some(Event::Key(event))
+        );
+        let written = Event::Key(event).encode(&mut buf, encoding).unwrap();
+        assert_eq!(buf[..written], *sequence);
+    }
+
+    // Characters that don't fit in 7 bits fall back to the ESC prefix
+    let written = Event::Key(KeyEvent::new(KeyCode::Char('é')).modifiers(KeyModifiers::ALT))
+        .encode(&mut buf, encoding)
+        .unwrap();
+    assert_eq!(buf[..written], *"\x1Bé".as_bytes());
+
+    // Without the option, the high bit starts a UTF-8 sequence
+    assert_eq!(Event::parse_from(b"\xE1").unwrap(), None);
+}
+
+#[test]
+fn test_keypad_application_mode() {
+    let encoding = Encoding::XtermWith(XtermOptions::new().application_keypad(true));
+    let mut buf = [0; 16];
+    for (sequence, code) in [
+        (b"\x1BOp", KeyCode::Char('0')),
+        (b"\x1BOy", KeyCode::Char('9')),
+        (b"\x1BOM", KeyCode::Enter),
+        (b"\x1BOX", KeyCode::Char('=')),
+        (b"\x1BOj", KeyCode::Char('*')),
+        (b"\x1BOk", KeyCode::Char('+')),
+        (b"\x1BOl", KeyCode::Char(',')),
+        (b"\x1BOm", KeyCode::Char('-')),
+        (b"\x1BOn", KeyCode::Char('.')),
+        (b"\x1BOo", KeyCode::Char('/')),
+    ] {
+        let event = Event::Key(KeyEvent::new(code).state(KeyEventState::KEYPAD));
+        assert_eq!(Event::parse_from(sequence).unwrap(), Some(event.clone()));
+        let written = event.encode(&mut buf, encoding).unwrap();
+        assert_eq!(buf[..written], *sequence);
+    }
+
+    // Keys that didn't come from the keypad are unaffected
+    let written = Event::Key(KeyCode::Char('1').into())
+        .encode(&mut buf, encoding)
+        .unwrap();
+    assert_eq!(buf[..written], *b"1");
+
+    // Without the option, keypad keys are sent as normal characters
+    let written = Event::Key(KeyEvent::new(KeyCode::Char('1')).state(KeyEventState::KEYPAD))
+        .encode(&mut buf, Encoding::Xterm)
+        .unwrap();
+    assert_eq!(buf[..written], *b"1");
+}
+
+#[test]
+fn test_application_cursor_keys() {
+    let encoding = Encoding::XtermWith(XtermOptions::new().application_cursor_keys(true));
+    let mut buf = [0; 16];
+    for (sequence, event) in [
+        (b"\x1BOA".as_slice(), KeyEvent::new(KeyCode::Up)),
+        (b"\x1BOB", KeyEvent::new(KeyCode::Down)),
+        (b"\x1BOC", KeyEvent::new(KeyCode::Right)),
+        (b"\x1BOD", KeyEvent::new(KeyCode::Left)),
+        (b"\x1BOH", KeyEvent::new(KeyCode::Home)),
+        (b"\x1BOF", KeyEvent::new(KeyCode::End)),
+        // Modified keys use the same sequences as normal cursor key mode
+        (
+            b"\x1B[1;5A",
+            KeyEvent::new(KeyCode::Up).modifiers(KeyModifiers::CTRL),
+        ),
+        (
+            b"\x1B[1;2H",
+            KeyEvent::new(KeyCode::Home).modifiers(KeyModifiers::SHIFT),
+        ),
+        (
+            b"\x1B[1;3F",
+            KeyEvent::new(KeyCode::End).modifiers(KeyModifiers::ALT),
+        ),
+        // Other keys are unaffected
+        (b"\x1B[5~", KeyEvent::new(KeyCode::PageUp)),
+    ] {
+        assert_eq!(
+            Event::parse_from(sequence).unwrap(),
+            Some(Event::Key(event))
+        );
+        let written = Event::Key(event).encode(&mut buf, encoding).unwrap();
+        assert_eq!(buf[..written], *sequence);
+    }
+
+    let written = Event::Key(KeyCode::Up.into())
+        .encode(&mut buf, Encoding::Xterm)
+        .unwrap();
+    assert_eq!(buf[..written], *b"\x1B[A");
+}
+
+#[test]
+fn test_rxvt_key_sequences() {
+    let encoding = Encoding::XtermWith(XtermOptions::new().family(KeySequenceFamily::Rxvt));
+    let mut buf = [0; 16];
+    for (sequence, event) in [
+        (b"\x1B[A".as_slice(), KeyEvent::new(KeyCode::Up)),
+        (
+            b"\x1B[a",
+            KeyEvent::new(KeyCode::Up).modifiers(KeyModifiers::SHIFT),
+        ),
+        (
+            b"\x1B[d",
+            KeyEvent::new(KeyCode::Left).modifiers(KeyModifiers::SHIFT),
+        ),
+        (
+            b"\x1BOa",
+            KeyEvent::new(KeyCode::Up).modifiers(KeyModifiers::CTRL),
+        ),
+        (
+            b"\x1BOc",
+            KeyEvent::new(KeyCode::Right).modifiers(KeyModifiers::CTRL),
+        ),
+        (
+            b"\x1B\x1B[A",
+            KeyEvent::new(KeyCode::Up).modifiers(KeyModifiers::ALT),
+        ),
+        (b"\x1B[7~", KeyEvent::new(KeyCode::Home)),
+        (b"\x1B[8~", KeyEvent::new(KeyCode::End)),
+        (
+            b"\x1B[2$",
+            KeyEvent::new(KeyCode::Insert).modifiers(KeyModifiers::SHIFT),
+        ),
+        (
+            b"\x1B[5^",
+            KeyEvent::new(KeyCode::PageUp).modifiers(KeyModifiers::CTRL),
+        ),
+        (
+            b"\x1B[3@",
+            KeyEvent::new(KeyCode::Delete).modifiers(KeyModifiers::CTRL | KeyModifiers::SHIFT),
+        ),
+        (b"\x1B[11~", KeyEvent::new(KeyCode::F(1))),
+        (
+            b"\x1B[24^",
+            KeyEvent::new(KeyCode::F(12)).modifiers(KeyModifiers::CTRL),
+        ),
+        (
+            b"\x1B\x1B[6$",
+            KeyEvent::new(KeyCode::PageDown).modifiers(KeyModifiers::ALT | KeyModifiers::SHIFT),
+        ),
+    ] {
+        assert_eq!(
+            Event::parse_from(sequence).unwrap(),
+            Some(Event::Key(event))
+        );
+        let written = Event::Key(event).encode(&mut buf, encoding).unwrap();
+        assert_eq!(buf[..written], *sequence);
+    }
+
+    // rxvt can't send other modifier combinations with arrow keys
+    let err =
+        Event::Key(KeyEvent::new(KeyCode::Up).modifiers(KeyModifiers::CTRL | KeyModifiers::SHIFT))
+            .encode(&mut buf, encoding)
+            .unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::Unsupported);
+
+    // Mode reports with the same intermediate byte are unaffected
+    assert_eq!(Event::parse_from(b"\x1B[4;1$").unwrap(), None);
+}
+
+#[test]
+fn test_linux_console_key_sequences() {
+    let encoding = Encoding::XtermWith(XtermOptions::new().family(KeySequenceFamily::LinuxConsole));
+    let mut buf = [0; 16];
+    for (sequence, event) in [
+        (b"\x1B[[A".as_slice(), KeyEvent::new(KeyCode::F(1))),
+        (b"\x1B[[E", KeyEvent::new(KeyCode::F(5))),
+        (b"\x1B[17~", KeyEvent::new(KeyCode::F(6))),
+        (b"\x1B[1~", KeyEvent::new(KeyCode::Home)),
+        (b"\x1B[4~", KeyEvent::new(KeyCode::End)),
+        (b"\x1B[A", KeyEvent::new(KeyCode::Up)),
+        (
+            b"\x1B\x1B[[B",
+            KeyEvent::new(KeyCode::F(2)).modifiers(KeyModifiers::ALT),
+        ),
+        (
+            b"\x1B\x1B[3~",
+            KeyEvent::new(KeyCode::Delete).modifiers(KeyModifiers::ALT),
+        ),
+    ] {
+        assert_eq!(
+            Event::parse_from(sequence).unwrap(),
+            Some(Event::Key(event))
+        );
+        let written = Event::Key(event).encode(&mut buf, encoding).unwrap();
+        assert_eq!(buf[..written], *sequence);
+    }
+
+    let err = Event::Key(KeyEvent::new(KeyCode::Home).modifiers(KeyModifiers::CTRL))
+        .encode(&mut buf, encoding)
+        .unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::Unsupported);
+}
+
+#[test]
+fn test_screen_key_sequences() {
+    let encoding = Encoding::XtermWith(XtermOptions::new().family(KeySequenceFamily::Screen));
+    let mut buf = [0; 16];
+    for (sequence, event) in [
+        (b"\x1B[1~".as_slice(), KeyEvent::new(KeyCode::Home)),
+        (b"\x1B[4~", KeyEvent::new(KeyCode::End)),
+        (
+            b"\x1B[1;5~",
+            KeyEvent::new(KeyCode::Home).modifiers(KeyModifiers::CTRL),
+        ),
+        (b"\x1B[A", KeyEvent::new(KeyCode::Up)),
+        (b"\x1B[5~", KeyEvent::new(KeyCode::PageUp)),
+    ] {
+        assert_eq!(
+            Event::parse_from(sequence).unwrap(),
+            Some(Event::Key(event))
+        );
+        let written = Event::Key(event).encode(&mut buf, encoding).unwrap();
+        assert_eq!(buf[..written], *sequence);
+    }
+}
+
+#[test]
+fn test_invalid_control_string() {
+    let alt_bracket = Event::Key(KeyEvent::new(KeyCode::Char(']')).modifiers(KeyModifiers::ALT));
+    let alt_p = Event::Key(
+        KeyEvent::new(KeyCode::Char('P')).modifiers(KeyModifiers::ALT | KeyModifiers::SHIFT),
+    );
+    // The introducer is parsed as a key typed with Alt
+    for (sequence, event) in [
+        (b"\x1B]x".as_slice(), &alt_bracket),
+        (b"\x1B]a", &alt_bracket),
+        (b"\x1B];", &alt_bracket),
+        (b"\x1B]1\r", &alt_bracket),
+        (b"\x1BPx", &alt_p),
+        (b"\x1BP>x", &alt_p),
+        (b"\x1BP1+\x7F", &alt_p),
+    ] {
+        assert_eq!(Event::parse_from(sequence).unwrap().as_ref(), Some(event));
+    }
+    // Valid prefixes wait for more input
+    assert_eq!(Event::parse_from(b"\x1B]11;rgb:").unwrap(), None);
+    assert_eq!(Event::parse_from(b"\x1BP1+r").unwrap(), None);
+    assert_eq!(
+        Event::parse_with_options(
+            b"\x1B]52;c;aGVsbG8=",
+            ParserOptions::new().max_control_string_len(8)
+        )
+        .unwrap(),
+        Some(alt_bracket)
+    );
+}
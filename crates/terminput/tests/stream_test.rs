@@ -0,0 +1,450 @@
+use std::cell::Cell;
+use std::time::{Duration, Instant};
+
+use terminput::{
+    Backend, ClipboardSelection, Clock, Event, EventSource, KeyCode, KeyEvent, KeyModifiers,
+    ParseErrorKind, Parser, ParserOptions, TerminalReply, TimedEvent,
+};
+
+struct TestClock {
+    start: Instant,
+    elapsed: Cell<Duration>,
+}
+
+impl TestClock {
+    fn new() -> Self {
+        Self {
+            start: Instant::now(),
+            elapsed: Cell::new(Duration::ZERO),
+        }
+    }
+
+    fn advance(&self, duration: Duration) {
+        self.elapsed.set(self.elapsed.get() + duration);
+    }
+}
+
+impl Clock for TestClock {
+    fn now(&self) -> Instant {
+        self.start + self.elapsed.get()
+    }
+}
+
+fn parse_all<C: Clock>(parser: &mut Parser<C>, input: &[u8]) -> Vec<(Event, usize)> {
+    parser.parse(input).collect::<Result<_, _>>().unwrap()
+}
+
+fn flush_expired<C: Clock>(parser: &mut Parser<C>) -> Vec<(Event, usize)> {
+    parser.flush_expired().collect::<Result<_, _>>().unwrap()
+}
+
+#[test]
+fn test_multiple_events_in_buffer() {
+    let mut parser = Parser::new();
+    assert_eq!(
+        parse_all(&mut parser, b"ab\x1B[A\x1B[1;5D\r"),
+        [
+            (Event::Key(KeyCode::Char('a').into()), 1),
+            (Event::Key(KeyCode::Char('b').into()), 1),
+            (Event::Key(KeyCode::Up.into()), 3),
+            (
+                Event::Key(KeyEvent::new(KeyCode::Left).modifiers(KeyModifiers::CTRL)),
+                6
+            ),
+            (Event::Key(KeyCode::Enter.into()), 1),
+        ]
+    );
+    assert!(parser.pending().is_empty());
+}
+
+#[test]
+fn test_paste_followed_by_key() {
+    let mut parser = Parser::new();
+    assert_eq!(
+        parse_all(&mut parser, b"\x1B[200~hello\x1B[201~x"),
+        [
+            (Event::Paste("hello".to_string()), 17),
+            (Event::Key(KeyCode::Char('x').into()), 1),
+        ]
+    );
+}
+
+#[test]
+fn test_sequence_split_across_calls() {
+    let mut parser = Parser::new();
+    assert_eq!(
+        parse_all(&mut parser, b"a\x1B[1;"),
+        [(Event::Key(KeyCode::Char('a').into()), 1)]
+    );
+    assert_eq!(parser.pending(), b"\x1B[1;");
+    assert_eq!(parse_all(&mut parser, b"5"), []);
+    assert_eq!(
+        parse_all(&mut parser, b"Db"),
+        [
+            (
+                Event::Key(KeyEvent::new(KeyCode::Left).modifiers(KeyModifiers::CTRL)),
+                6
+            ),
+            (Event::Key(KeyCode::Char('b').into()), 1),
+        ]
+    );
+
+    assert_eq!(parse_all(&mut parser, &"é".as_bytes()[..1]), []);
+    assert_eq!(
+        parse_all(&mut parser, &"é".as_bytes()[1..]),
+        [(Event::Key(KeyCode::Char('é').into()), 2)]
+    );
+}
+
+#[test]
+fn test_reply_split_across_calls() {
+    let mut parser = Parser::new();
+    assert_eq!(parse_all(&mut parser, b"\x1B]52;c;aGVs"), []);
+    assert_eq!(parse_all(&mut parser, b"bG8=\x1B"), []);
+    assert_eq!(
+        parse_all(&mut parser, b"\\a"),
+        [
+            (
+                Event::Reply(TerminalReply::Clipboard {
+                    selection: ClipboardSelection::Clipboard,
+                    data: b"hello".to_vec(),
+                }),
+                17
+            ),
+            (Event::Key(KeyCode::Char('a').into()), 1),
+        ]
+    );
+}
+
+#[test]
+fn test_escape_handling() {
+    let mut parser = Parser::new();
+    assert_eq!(
+        parse_all(&mut parser, b"\x1B"),
+        [(Event::Key(KeyCode::Esc.into()), 1)]
+    );
+    assert_eq!(
+        parse_all(&mut parser, b"\x1Ba\x1B"),
+        [
+            (
+                Event::Key(KeyEvent::new(KeyCode::Char('a')).modifiers(KeyModifiers::ALT)),
+                2
+            ),
+            (Event::Key(KeyCode::Esc.into()), 1),
+        ]
+    );
+    assert_eq!(
+        parse_all(&mut parser, b"\x1B\x1B"),
+        [(
+            Event::Key(KeyEvent::new(KeyCode::Esc).modifiers(KeyModifiers::ALT)),
+            2
+        )]
+    );
+    assert_eq!(
+        parse_all(&mut parser, b"\x1B\x1B[Z"),
+        [(
+            Event::Key(
+                KeyEvent::new(KeyCode::Tab).modifiers(KeyModifiers::SHIFT | KeyModifiers::ALT)
+            ),
+            4
+        )]
+    );
+}
+
+#[test]
+fn test_invalid_sequence() {
+    let mut parser = Parser::new();
+    let mut events = parser.parse(b"\x1BOZa");
+    let err = events.next().unwrap().unwrap_err();
+    assert_eq!(err.kind(), ParseErrorKind::UnknownSs3Final(b'Z'));
+    assert_eq!(err.bytes(), b"\x1BOZ");
+    assert_eq!(
+        events.next().unwrap().unwrap(),
+        (Event::Key(KeyCode::Char('a').into()), 1)
+    );
+    assert!(events.next().is_none());
+}
+
+#[test]
+fn test_resync_after_invalid_sequence() {
+    let mut parser = Parser::new();
+    let mut events = parser.parse(b"\x1B[1\x1B[Ab");
+    let err = events.next().unwrap().unwrap_err();
+    assert_eq!(err.kind(), ParseErrorKind::MalformedParameters);
+    assert_eq!(err.bytes(), b"\x1B[1\x1B[");
+    assert_eq!(
+        events.next().unwrap().unwrap(),
+        (Event::Key(KeyCode::Up.into()), 3)
+    );
+    assert_eq!(
+        events.next().unwrap().unwrap(),
+        (Event::Key(KeyCode::Char('b').into()), 1)
+    );
+    assert!(events.next().is_none());
+}
+
+#[test]
+fn test_escape_timeout() {
+    let clock = TestClock::new();
+    let mut parser = Parser::with_clock(&clock).escape_timeout(Duration::from_millis(50));
+
+    assert_eq!(parse_all(&mut parser, b"\x1B"), []);
+    assert_eq!(parser.timeout(), Some(Duration::from_millis(50)));
+    clock.advance(Duration::from_millis(20));
+    assert_eq!(parser.timeout(), Some(Duration::from_millis(30)));
+    assert_eq!(flush_expired(&mut parser), []);
+
+    clock.advance(Duration::from_millis(30));
+    assert_eq!(parser.timeout(), Some(Duration::ZERO));
+    assert_eq!(
+        flush_expired(&mut parser),
+        [(Event::Key(KeyCode::Esc.into()), 1)]
+    );
+    assert_eq!(parser.timeout(), None);
+    assert!(parser.pending().is_empty());
+}
+
+#[test]
+fn test_escape_timeout_sequence_completed() {
+    let clock = TestClock::new();
+    let mut parser = Parser::with_clock(&clock).escape_timeout(Duration::from_millis(50));
+
+    assert_eq!(parse_all(&mut parser, b"\x1B"), []);
+    clock.advance(Duration::from_millis(40));
+    assert_eq!(parse_all(&mut parser, b"["), []);
+    // Receiving more input restarts the timeout
+    assert_eq!(parser.timeout(), Some(Duration::from_millis(50)));
+    clock.advance(Duration::from_millis(40));
+    assert_eq!(
+        parse_all(&mut parser, b"A"),
+        [(Event::Key(KeyCode::Up.into()), 3)]
+    );
+    assert_eq!(parser.timeout(), None);
+}
+
+#[test]
+fn test_escape_timeout_flush_as_alt() {
+    let clock = TestClock::new();
+    let mut parser = Parser::with_clock(&clock).escape_timeout(Duration::from_millis(50));
+
+    assert_eq!(parse_all(&mut parser, b"\x1B["), []);
+    clock.advance(Duration::from_millis(50));
+    assert_eq!(
+        flush_expired(&mut parser),
+        [(
+            Event::Key(KeyEvent::new(KeyCode::Char('[')).modifiers(KeyModifiers::ALT)),
+            2
+        )]
+    );
+
+    assert_eq!(parse_all(&mut parser, b"\x1BO"), []);
+    clock.advance(Duration::from_millis(50));
+    assert_eq!(
+        flush_expired(&mut parser),
+        [(
+            Event::Key(
+                KeyEvent::new(KeyCode::Char('O'))
+                    .modifiers(KeyModifiers::ALT | KeyModifiers::SHIFT)
+            ),
+            2
+        )]
+    );
+
+    assert_eq!(parse_all(&mut parser, b"\x1B[1;"), []);
+    clock.advance(Duration::from_millis(50));
+    assert_eq!(
+        flush_expired(&mut parser),
+        [
+            (
+                Event::Key(KeyEvent::new(KeyCode::Char('[')).modifiers(KeyModifiers::ALT)),
+                2
+            ),
+            (Event::Key(KeyCode::Char('1').into()), 1),
+            (Event::Key(KeyCode::Char(';').into()), 1),
+        ]
+    );
+
+    // Expired input is flushed before parsing new input
+    assert_eq!(parse_all(&mut parser, b"\x1B\x1B"), []);
+    clock.advance(Duration::from_millis(50));
+    assert_eq!(
+        parse_all(&mut parser, b"a"),
+        [
+            (
+                Event::Key(KeyEvent::new(KeyCode::Esc).modifiers(KeyModifiers::ALT)),
+                2
+            ),
+            (Event::Key(KeyCode::Char('a').into()), 1),
+        ]
+    );
+}
+
+#[test]
+fn test_escape_timeout_paste() {
+    let clock = TestClock::new();
+    let mut parser = Parser::with_clock(&clock).escape_timeout(Duration::from_millis(50));
+
+    assert_eq!(parse_all(&mut parser, b"\x1B[200~hello"), []);
+    assert_eq!(parser.timeout(), None);
+    clock.advance(Duration::from_millis(100));
+    assert_eq!(flush_expired(&mut parser), []);
+    assert_eq!(
+        parse_all(&mut parser, b" world\x1B[201~"),
+        [(Event::Paste("hello world".to_string()), 23)]
+    );
+}
+
+#[test]
+fn test_flush() {
+    let mut parser = Parser::new().escape_timeout(Duration::from_secs(60));
+    assert_eq!(parse_all(&mut parser, b"\x1B"), []);
+    assert_eq!(
+        parser.flush().collect::<Result<Vec<_>, _>>().unwrap(),
+        [(Event::Key(KeyCode::Esc.into()), 1)]
+    );
+}
+
+#[test]
+fn test_parse_timed() {
+    let clock = TestClock::new();
+    let mut parser = Parser::with_clock(&clock).escape_timeout(Duration::from_millis(50));
+
+    clock.advance(Duration::from_millis(10));
+    let events = parser
+        .parse_timed(b"a\x1B[A\x1B")
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(
+        events,
+        [
+            TimedEvent::new(
+                KeyEvent::new(KeyCode::Char('a')),
+                Duration::from_millis(10),
+                EventSource::new(Backend::Parser).raw(b"a")
+            ),
+            TimedEvent::new(
+                KeyEvent::new(KeyCode::Up),
+                Duration::from_millis(10),
+                EventSource::new(Backend::Parser).raw(b"\x1B[A")
+            ),
+        ]
+    );
+
+    // Flushed sequences keep their raw bytes
+    clock.advance(Duration::from_millis(50));
+    let events = parser
+        .parse_timed(b"")
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(
+        events,
+        [TimedEvent::new(
+            KeyEvent::new(KeyCode::Esc),
+            Duration::from_millis(60),
+            EventSource::new(Backend::Parser).raw(b"\x1B")
+        )]
+    );
+    assert_eq!(parser.epoch(), clock.start);
+}
+
+#[test]
+fn test_partially_consumed_input() {
+    let mut parser = Parser::new();
+    let mut events = parser.parse(b"a\x1B[Ab");
+    assert_eq!(
+        events.next().unwrap().unwrap(),
+        (Event::Key(KeyCode::Char('a').into()), 1)
+    );
+    drop(events);
+
+    // The unread input is parsed before the new input
+    assert_eq!(
+        parse_all(&mut parser, b"c"),
+        [
+            (Event::Key(KeyCode::Up.into()), 3),
+            (Event::Key(KeyCode::Char('b').into()), 1),
+            (Event::Key(KeyCode::Char('c').into()), 1),
+        ]
+    );
+
+    let mut events = parser.parse(b"de");
+    assert!(events.next().is_some());
+    drop(events);
+    assert_eq!(
+        parser.flush().collect::<Result<Vec<_>, _>>().unwrap(),
+        [(Event::Key(KeyCode::Char('e').into()), 1)]
+    );
+}
+
+#[test]
+fn test_alt_control_string_introducer() {
+    let mut parser = Parser::new().escape_timeout(Duration::from_secs(60));
+    assert_eq!(
+        parse_all(&mut parser, b"\x1B]a"),
+        [
+            (
+                Event::Key(KeyEvent::new(KeyCode::Char(']')).modifiers(KeyModifiers::ALT)),
+                2
+            ),
+            (Event::Key(KeyCode::Char('a').into()), 1),
+        ]
+    );
+    assert_eq!(
+        parse_all(&mut parser, b"\x1BPx"),
+        [
+            (
+                Event::Key(
+                    KeyEvent::new(KeyCode::Char('P'))
+                        .modifiers(KeyModifiers::ALT | KeyModifiers::SHIFT)
+                ),
+                2
+            ),
+            (Event::Key(KeyCode::Char('x').into()), 1),
+        ]
+    );
+
+    // Bytes that look like the start of a reply are kept until an invalid byte arrives
+    assert_eq!(parse_all(&mut parser, b"\x1B]1"), []);
+    assert_eq!(
+        parse_all(&mut parser, b"2\r"),
+        [
+            (
+                Event::Key(KeyEvent::new(KeyCode::Char(']')).modifiers(KeyModifiers::ALT)),
+                2
+            ),
+            (Event::Key(KeyCode::Char('1').into()), 1),
+            (Event::Key(KeyCode::Char('2').into()), 1),
+            (Event::Key(KeyCode::Enter.into()), 1),
+        ]
+    );
+    assert!(parser.pending().is_empty());
+}
+
+#[test]
+fn test_control_string_length_limit() {
+    let mut parser = Parser::new()
+        .options(ParserOptions::new().max_control_string_len(8))
+        .escape_timeout(Duration::from_secs(60));
+    assert_eq!(parse_all(&mut parser, b"\x1B]52;c;aGV"), []);
+    let events = parse_all(&mut parser, b"s");
+    assert_eq!(
+        events[0],
+        (
+            Event::Key(KeyEvent::new(KeyCode::Char(']')).modifiers(KeyModifiers::ALT)),
+            2
+        )
+    );
+    assert_eq!(events.len(), 10);
+
+    // Replies within the limit are still parsed
+    assert_eq!(
+        parse_all(&mut parser, b"\x1B]52;c;\x07"),
+        [(
+            Event::Reply(TerminalReply::Clipboard {
+                selection: ClipboardSelection::Clipboard,
+                data: Vec::new(),
+            }),
+            8
+        )]
+    );
+}
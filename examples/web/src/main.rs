@@ -1,7 +1,8 @@
-use terminput::Event;
+use terminput::{CellGeometry, Event};
 use terminput_web_sys::{
-    to_terminput_key, to_terminput_mouse, to_terminput_mouse_drag, to_terminput_mouse_scroll,
-    to_terminput_paste, to_terminput_resize,
+    to_terminput_key, to_terminput_mouse_drag_with_geometry,
+    to_terminput_mouse_scroll_with_geometry, to_terminput_mouse_with_geometry, to_terminput_paste,
+    to_terminput_resize,
 };
 use wasm_bindgen::JsCast;
 use wasm_bindgen::prelude::Closure;
@@ -13,11 +14,14 @@ fn main() {
     let window = window().unwrap();
     let document = window.document().unwrap();
     let body = document.body().unwrap();
+    // A terminal widget would use its own offset and font size here. The default geometry reports
+    // positions in CSS pixels.
+    let geometry = CellGeometry::default();
 
     let on_mouse = Closure::<dyn Fn(MouseEvent)>::new({
         let document = document.clone();
         move |e| {
-            let mouse_event = to_terminput_mouse(e);
+            let mouse_event = to_terminput_mouse_with_geometry(e, &geometry);
             append_text(&document, &format!("{mouse_event:?}\n"));
         }
     });
@@ -33,7 +37,7 @@ fn main() {
     let on_drag = Closure::<dyn Fn(DragEvent)>::new({
         let document = document.clone();
         move |e| {
-            let drag_event = to_terminput_mouse_drag(e);
+            let drag_event = to_terminput_mouse_drag_with_geometry(e, &geometry);
             append_text(&document, &format!("{drag_event:?}\n"));
         }
     });
@@ -66,7 +70,7 @@ fn main() {
     let on_wheel = Closure::<dyn Fn(WheelEvent)>::new({
         let document = document.clone();
         move |e| {
-            let scroll_event = to_terminput_mouse_scroll(e);
+            let scroll_event = to_terminput_mouse_scroll_with_geometry(e, &geometry);
             append_text(&document, &format!("{scroll_event:?}\n"));
         }
     });